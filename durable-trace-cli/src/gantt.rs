@@ -0,0 +1,143 @@
+use durable_trace::analysis;
+use durable_trace::DurableTrace;
+
+const MAX_LABEL_WIDTH: usize = 30;
+
+/// Characters used to draw the chart.
+#[derive(Debug, Clone, Copy)]
+pub struct Glyphs {
+    completed: char,
+    running: char,
+    empty: char,
+    border: char,
+    // Ends labels that were cut short.
+    ellipsis: char,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    completed: '█',
+    running: '░',
+    empty: ' ',
+    border: '│',
+    ellipsis: '…',
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    completed: '#',
+    running: '.',
+    empty: ' ',
+    border: '|',
+    ellipsis: '~',
+};
+
+/// Renders one row per step, positioned on a window from the execution start to its latest
+/// completion, followed by an axis with the window bounds.
+pub fn render(trace: &DurableTrace, width: usize, glyphs: Glyphs) -> String {
+    let width = width.max(10);
+    let window_start = trace.scheduled_at;
+    let window_end =
+        analysis::find_max_completion(&trace.steps, trace.scheduled_at, trace.completed_at);
+    let window = (window_end - window_start).num_milliseconds().max(1) as f64;
+    let column = |offset_ms: i64| -> usize {
+        ((offset_ms as f64 / window) * width as f64)
+            .round()
+            .clamp(0.0, width as f64) as usize
+    };
+
+    let label_width = trace
+        .steps
+        .iter()
+        .map(|step| step.durable_step_id.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(4, MAX_LABEL_WIDTH);
+
+    let mut out = String::new();
+    for step in &trace.steps {
+        let start = column((step.scheduled_at - window_start).num_milliseconds());
+        let (end, fill, duration) = match analysis::step_duration(step) {
+            Some(duration) => (
+                column((step.scheduled_at + duration - window_start).num_milliseconds()),
+                glyphs.completed,
+                analysis::compact_duration(duration),
+            ),
+            None => (width, glyphs.running, "running".to_string()),
+        };
+        // Always draw at least one cell so instantaneous steps stay visible.
+        let end = end.max(start + 1).min(width);
+        let start = start.min(end.saturating_sub(1));
+
+        let bar: String = (0..width)
+            .map(|i| {
+                if i >= start && i < end {
+                    fill
+                } else {
+                    glyphs.empty
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "{:<label_width$} {}{}{} {}\n",
+            truncate(&step.durable_step_id, label_width, glyphs.ellipsis),
+            glyphs.border,
+            bar,
+            glyphs.border,
+            duration,
+        ));
+    }
+
+    let start_label = window_start.to_rfc3339();
+    let end_label = window_end.to_rfc3339();
+    let gap = (width + 2)
+        .saturating_sub(start_label.len() + end_label.len())
+        .max(1);
+    out.push_str(&format!(
+        "{:<label_width$} {}{}{}\n",
+        "",
+        start_label,
+        " ".repeat(gap),
+        end_label
+    ));
+    out
+}
+
+fn truncate(text: &str, width: usize, ellipsis: char) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push(ellipsis);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use durable_trace::chrono::{DateTime, TimeDelta};
+    use durable_trace::{StepTrace, TaskInfo};
+
+    #[test]
+    fn ascii_chart_is_ascii() {
+        let at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        let step = StepTrace::builder(
+            "a-step-id-far-longer-than-any-label-column",
+            at,
+            TaskInfo::builder("task-1", "charge", at).build(),
+        )
+        .completed_at(at + TimeDelta::seconds(30))
+        .build();
+        let trace = DurableTrace::builder("order", "exec-1", at)
+            .status("completed")
+            .completed_at(at + TimeDelta::minutes(1))
+            .steps([step])
+            .build();
+        let chart = render(&trace, 40, ASCII);
+        assert!(chart.is_ascii(), "{}", chart);
+        assert!(
+            chart.contains("a-step-id-far-longer-than-any~"),
+            "{}",
+            chart
+        );
+        assert!(render(&trace, 40, UNICODE).contains("a-step-id-far-longer-than-any…"));
+    }
+}
@@ -0,0 +1,214 @@
+use clap::Parser;
+use durable_trace::import::{self, TraceFormat};
+use durable_trace::validation::{self, Finding};
+use durable_trace::{analysis, DurableTrace};
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod gantt;
+
+/// Inspect a durable execution trace in the terminal.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Trace file to read. Reads stdin when omitted or `-`.
+    file: Option<PathBuf>,
+
+    /// Input format: durable, otlp, temporal or step-functions. Detected from the document when
+    /// omitted.
+    #[arg(long, value_parser = parse_format)]
+    format: Option<TraceFormat>,
+
+    /// Print a machine readable report instead of text.
+    #[arg(long)]
+    json: bool,
+
+    /// Width of the chart area in columns.
+    #[arg(long, default_value_t = 60)]
+    width: usize,
+
+    /// Draw the chart with plain ASCII characters.
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report<'a> {
+    name: &'a str,
+    durable_execution_id: &'a str,
+    status: &'a str,
+    version: u32,
+    scheduled_at: String,
+    completed_at: Option<String>,
+    duration_seconds: Option<i64>,
+    steps: Vec<StepReport<'a>>,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepReport<'a> {
+    durable_step_id: &'a str,
+    task_name: &'a str,
+    scheduled_at: String,
+    completed_at: Option<String>,
+    offset_seconds: i64,
+    duration_seconds: Option<i64>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let input = match read_input(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let trace = match import::import(&input, args.format) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let findings = validation::validate(&trace);
+
+    let mut out = io::stdout().lock();
+    match print(&mut out, &args, &trace, findings).and_then(|()| out.flush()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader went away, e.g. `| head`; nothing is left to tell it.
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print(
+    out: &mut impl Write,
+    args: &Args,
+    trace: &DurableTrace,
+    findings: Vec<Finding>,
+) -> io::Result<()> {
+    if args.json {
+        let report = report(trace, findings);
+        writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        )
+    } else {
+        print_summary(out, trace)?;
+        writeln!(out)?;
+        print_findings(out, &findings)?;
+        writeln!(out)?;
+        let glyphs = if args.ascii {
+            gantt::ASCII
+        } else {
+            gantt::UNICODE
+        };
+        write!(out, "{}", gantt::render(trace, args.width, glyphs))
+    }
+}
+
+fn parse_format(key: &str) -> Result<TraceFormat, String> {
+    TraceFormat::from_key(key).ok_or_else(|| {
+        let keys = TraceFormat::ALL.map(|format| format.key());
+        format!("expected one of: {}", keys.join(", "))
+    })
+}
+
+fn read_input(args: &Args) -> io::Result<String> {
+    match &args.file {
+        Some(path) if path.as_os_str() != "-" => std::fs::read_to_string(path),
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+fn print_summary(out: &mut impl Write, trace: &DurableTrace) -> io::Result<()> {
+    let completed_steps = trace
+        .steps
+        .iter()
+        .filter(|step| step.completed_at.is_some())
+        .count();
+    writeln!(out, "Durable Trace ({})", trace.name)?;
+    writeln!(out, "  Id:          {}", trace.durable_execution_id)?;
+    writeln!(out, "  Status:      {}", trace.status)?;
+    writeln!(out, "  Version:     {}", trace.version)?;
+    writeln!(out, "  Scheduled:   {}", trace.scheduled_at.to_rfc3339())?;
+    writeln!(
+        out,
+        "  Completed:   {}",
+        trace
+            .completed_at
+            .map(|completed_at| completed_at.to_rfc3339())
+            .unwrap_or_else(|| "-".to_string())
+    )?;
+    writeln!(
+        out,
+        "  Duration:    {}",
+        analysis::trace_duration(trace)
+            .map(analysis::compact_duration)
+            .unwrap_or_else(|| "-".to_string())
+    )?;
+    writeln!(
+        out,
+        "  Steps:       {} ({} completed)",
+        trace.steps.len(),
+        completed_steps
+    )?;
+    if let Some(failure_reason) = &trace.failure_reason {
+        writeln!(out, "  Failure:     {}", failure_reason)?;
+    }
+    Ok(())
+}
+
+fn print_findings(out: &mut impl Write, findings: &[Finding]) -> io::Result<()> {
+    if findings.is_empty() {
+        writeln!(out, "No validation findings.")?;
+        return Ok(());
+    }
+    writeln!(out, "Validation findings:")?;
+    for finding in findings {
+        writeln!(out, "  {}", finding)?;
+    }
+    Ok(())
+}
+
+fn report(trace: &DurableTrace, findings: Vec<Finding>) -> Report<'_> {
+    Report {
+        name: &trace.name,
+        durable_execution_id: &trace.durable_execution_id,
+        status: &trace.status,
+        version: trace.version,
+        scheduled_at: trace.scheduled_at.to_rfc3339(),
+        completed_at: trace
+            .completed_at
+            .map(|completed_at| completed_at.to_rfc3339()),
+        duration_seconds: analysis::trace_duration(trace).map(|delta| delta.num_seconds()),
+        steps: trace
+            .steps
+            .iter()
+            .map(|step| StepReport {
+                durable_step_id: &step.durable_step_id,
+                task_name: &step.in_task_info.task_name,
+                scheduled_at: step.scheduled_at.to_rfc3339(),
+                completed_at: step
+                    .completed_at
+                    .map(|completed_at| completed_at.to_rfc3339()),
+                offset_seconds: (step.scheduled_at - trace.scheduled_at).num_seconds(),
+                duration_seconds: analysis::step_duration(step).map(|delta| delta.num_seconds()),
+            })
+            .collect(),
+        findings,
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{DurableTrace, Marker, StepTrace};
+use chrono::{DateTime, TimeDelta, Utc};
+
+pub mod fleet;
+pub mod outliers;
+
+/// `delta` in words, such as `1 Hour and 10 Minutes`. Negative durations, which clock skew
+/// between systems can produce, get a leading `-`.
+pub fn get_duration_string(delta: TimeDelta) -> String {
+    let total_seconds = delta.num_seconds().unsigned_abs();
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+
+    let mut result = String::new();
+
+    if hours > 0 {
+        result.push_str(&format!(
+            "{} Hour{}",
+            hours,
+            if hours > 1 { "s" } else { "" }
+        ));
+    }
+
+    if minutes > 0 {
+        if !result.is_empty() {
+            result.push_str(" and ");
+        }
+        result.push_str(&format!(
+            "{} Minute{}",
+            minutes,
+            if minutes > 1 { "s" } else { "" }
+        ));
+    }
+
+    if seconds > 0 && result.is_empty() {
+        result.push_str(&format!(
+            "{} Second{}",
+            seconds,
+            if seconds > 1 { "s" } else { "" }
+        ));
+    }
+
+    if result.is_empty() {
+        result.push_str("0 Seconds"); // Handle zero duration case
+    } else if delta < TimeDelta::zero() {
+        result.insert(0, '-');
+    }
+
+    result
+}
+
+// Based on all steps, tries to find the max completion time
+pub fn find_max_completion(
+    steps: &[StepTrace],
+    durable_scheduled_at: DateTime<Utc>,
+    durable_completed_at: Option<DateTime<Utc>>,
+) -> DateTime<Utc> {
+    let max_completion = steps
+        .iter()
+        .map(|step| step.completed_at)
+        .reduce(|a, b| a.max(b))
+        .unwrap_or_default()
+        .max(durable_completed_at)
+        .or_else(|| durable_scheduled_at.checked_add_signed(TimeDelta::seconds(15)));
+    max_completion.unwrap_or(durable_scheduled_at)
+}
+
+/// Time between a step being scheduled and completed, if it has completed.
+pub fn step_duration(step: &StepTrace) -> Option<TimeDelta> {
+    step.completed_at
+        .map(|completed_at| completed_at - step.scheduled_at)
+}
+
+/// Time between the execution being scheduled and completed, if it has completed.
+pub fn trace_duration(trace: &DurableTrace) -> Option<TimeDelta> {
+    trace
+        .completed_at
+        .map(|completed_at| completed_at.signed_duration_since(trace.scheduled_at))
+}
+
+/// Short form of a duration such as `1h10m12s`, `45s` or `-3s`.
+pub fn compact_duration(delta: TimeDelta) -> String {
+    let sign = if delta < TimeDelta::zero() { "-" } else { "" };
+    let total_seconds = delta.num_seconds().unsigned_abs();
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    match (hours, minutes) {
+        (0, 0) => format!("{}{}s", sign, seconds),
+        (0, _) => format!("{}{}m{}s", sign, minutes, seconds),
+        _ => format!("{}{}h{}m{}s", sign, hours, minutes, seconds),
+    }
+}
+
+/// Like [`compact_duration`], but keeps milliseconds below a minute: `850ms`, `12.3s`.
+pub fn fine_duration(delta: TimeDelta) -> String {
+    let millis = delta.num_milliseconds();
+    match millis.unsigned_abs() {
+        0..=999 => format!("{}ms", millis),
+        1_000..=59_999 => format!("{:.1}s", millis as f64 / 1000.0),
+        _ => compact_duration(delta),
+    }
+}
+
+/// `at` as an offset from `origin` with millisecond precision: `T+01:10:12.120`, or `T-00:00:01.500`
+/// for times before it.
+pub fn relative_time(at: DateTime<Utc>, origin: DateTime<Utc>) -> String {
+    let delta = at - origin;
+    let sign = if delta < TimeDelta::zero() { "-" } else { "+" };
+    let millis = delta.num_milliseconds().unsigned_abs();
+    format!(
+        "T{}{:02}:{:02}:{:02}.{:03}",
+        sign,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Horizontal placement of a step bar, in percent of the timeline window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarPosition {
+    pub left: f64,
+    pub width: f64,
+}
+
+/// Places `step` on a timeline spanning `window_start..window_end`.
+///
+/// Steps that have not completed get a fixed 2% width so they stay visible.
+pub fn bar_position(
+    step: &StepTrace,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> BarPosition {
+    let delta_window = ((window_end - window_start).num_seconds() as f64).max(1.0);
+    let left = (step.scheduled_at - window_start).num_seconds() as f64 * 100.0 / delta_window;
+    let width = step_duration(step)
+        .map(|duration| duration.num_seconds() as f64 * 100.0 / delta_window)
+        .unwrap_or(2.0);
+    BarPosition { left, width }
+}
+
+/// Places `marker` on a timeline spanning `window_start..window_end`. Points have no width.
+pub fn marker_position(
+    marker: &Marker,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> BarPosition {
+    let delta_window = ((window_end - window_start).num_seconds() as f64).max(1.0);
+    let left = (marker.start - window_start).num_seconds() as f64 * 100.0 / delta_window;
+    let width = marker
+        .end
+        .map(|end| (end - marker.start).num_seconds() as f64 * 100.0 / delta_window)
+        .unwrap_or(0.0);
+    BarPosition { left, width }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_string() {
+        assert_eq!(get_duration_string(TimeDelta::seconds(0)), "0 Seconds");
+        assert_eq!(get_duration_string(TimeDelta::seconds(45)), "45 Seconds");
+        assert_eq!(
+            get_duration_string(TimeDelta::seconds(3 * 3600 + 60)),
+            "3 Hours and 1 Minute"
+        );
+    }
+
+    #[test]
+    fn negative_duration_string_does_not_panic() {
+        assert_eq!(get_duration_string(TimeDelta::seconds(-90)), "-1 Minute");
+    }
+
+    #[test]
+    fn max_completion_without_steps() {
+        let scheduled_at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        assert_eq!(
+            find_max_completion(&[], scheduled_at, None),
+            scheduled_at + TimeDelta::seconds(15)
+        );
+        assert_eq!(
+            find_max_completion(&[], DateTime::<Utc>::MAX_UTC, None),
+            DateTime::<Utc>::MAX_UTC
+        );
+    }
+}
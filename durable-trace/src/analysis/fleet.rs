@@ -0,0 +1,309 @@
+//! Statistics across many traces, for spotting slow or flaky workflows and steps.
+
+use crate::analysis::{step_duration, trace_duration};
+use crate::DurableTrace;
+use chrono::TimeDelta;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Buckets in each workflow's duration histogram.
+pub const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Nearest-rank percentiles of a set of durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Percentiles {
+    pub p50: TimeDelta,
+    pub p95: TimeDelta,
+    pub p99: TimeDelta,
+    pub max: TimeDelta,
+}
+
+impl Percentiles {
+    /// `None` when there are no durations.
+    pub fn of(durations: &[TimeDelta]) -> Option<Self> {
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        Some(Percentiles {
+            p50: percentile(&sorted, 50.0)?,
+            p95: percentile(&sorted, 95.0)?,
+            p99: percentile(&sorted, 99.0)?,
+            max: *sorted.last()?,
+        })
+    }
+}
+
+/// The duration at percentile `p` (0-100) of `sorted`, by nearest rank.
+pub fn percentile(sorted: &[TimeDelta], p: f64) -> Option<TimeDelta> {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+/// Durations in `start..end`, or up to and including `end` for the last bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: TimeDelta,
+    pub end: TimeDelta,
+    pub count: usize,
+}
+
+/// Splits the range of `durations` into `buckets` equally wide buckets.
+pub fn histogram(durations: &[TimeDelta], buckets: usize) -> Vec<Bucket> {
+    let (Some(min), Some(max)) = (durations.iter().min(), durations.iter().max()) else {
+        return Vec::new();
+    };
+    let buckets = buckets.max(1);
+    // At least a millisecond per bucket, so identical durations still get a range.
+    let width = ((*max - *min).num_milliseconds() / buckets as i64).max(1);
+    let mut histogram: Vec<Bucket> = (0..buckets as i64)
+        .map(|i| Bucket {
+            start: *min + TimeDelta::milliseconds(width * i),
+            end: *min + TimeDelta::milliseconds(width * (i + 1)),
+            count: 0,
+        })
+        .collect();
+    // Rounding the width down leaves a remainder, which the last bucket takes.
+    if let Some(last) = histogram.last_mut() {
+        last.end = last.end.max(*max);
+    }
+    for duration in durations {
+        let index = ((*duration - *min).num_milliseconds() / width) as usize;
+        histogram[index.min(buckets - 1)].count += 1;
+    }
+    histogram
+}
+
+/// Completed durations of one workflow `name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkflowStats {
+    pub name: String,
+    pub executions: usize,
+    pub completed: usize,
+    pub percentiles: Option<Percentiles>,
+    pub histogram: Vec<Bucket>,
+}
+
+/// Completed durations of every step sharing a `durable_step_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepStats {
+    pub durable_step_id: String,
+    pub occurrences: usize,
+    pub percentiles: Option<Percentiles>,
+}
+
+/// Failures of the tasks behind a `durable_step_id`, summed over its occurrences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailingStep {
+    pub durable_step_id: String,
+    pub consecutive_failures: u64,
+    pub failed_occurrences: usize,
+}
+
+/// Everything the dashboard shows about a set of traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FleetSummary {
+    pub traces: usize,
+    pub by_status: Vec<(String, usize)>,
+    pub by_name: Vec<(String, usize)>,
+    pub workflows: Vec<WorkflowStats>,
+    pub steps: Vec<StepStats>,
+    /// Most failures first; steps that never failed are left out.
+    pub failing_steps: Vec<FailingStep>,
+}
+
+pub fn summarize(traces: &[DurableTrace]) -> FleetSummary {
+    let mut by_status: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut workflows: BTreeMap<&str, (usize, Vec<TimeDelta>)> = BTreeMap::new();
+    let mut steps: BTreeMap<&str, (usize, Vec<TimeDelta>)> = BTreeMap::new();
+    let mut failures: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+
+    for trace in traces {
+        *by_status.entry(&trace.status).or_default() += 1;
+        let workflow = workflows.entry(&trace.name).or_default();
+        workflow.0 += 1;
+        workflow.1.extend(trace_duration(trace));
+
+        for step in &trace.steps {
+            let stats = steps.entry(&step.durable_step_id).or_default();
+            stats.0 += 1;
+            stats.1.extend(step_duration(step));
+
+            let step_failures: u64 = [Some(&step.in_task_info), step.out_task_info.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|info| u64::from(info.consecutive_failures))
+                .sum();
+            if step_failures > 0 {
+                let failing = failures.entry(&step.durable_step_id).or_default();
+                failing.0 += step_failures;
+                failing.1 += 1;
+            }
+        }
+    }
+
+    let mut by_name: Vec<(String, usize)> = workflows
+        .iter()
+        .map(|(name, (executions, _))| (name.to_string(), *executions))
+        .collect();
+    by_name.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut by_status: Vec<(String, usize)> = by_status
+        .into_iter()
+        .map(|(status, count)| (status.to_string(), count))
+        .collect();
+    by_status.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut failing_steps: Vec<FailingStep> = failures
+        .into_iter()
+        .map(
+            |(durable_step_id, (consecutive_failures, failed_occurrences))| FailingStep {
+                durable_step_id: durable_step_id.to_string(),
+                consecutive_failures,
+                failed_occurrences,
+            },
+        )
+        .collect();
+    failing_steps.sort_by_key(|step| Reverse(step.consecutive_failures));
+
+    FleetSummary {
+        traces: traces.len(),
+        by_status,
+        by_name,
+        workflows: workflows
+            .into_iter()
+            .map(|(name, (executions, durations))| WorkflowStats {
+                name: name.to_string(),
+                executions,
+                completed: durations.len(),
+                percentiles: Percentiles::of(&durations),
+                histogram: histogram(&durations, HISTOGRAM_BUCKETS),
+            })
+            .collect(),
+        steps: steps
+            .into_iter()
+            .map(|(durable_step_id, (occurrences, durations))| StepStats {
+                durable_step_id: durable_step_id.to_string(),
+                occurrences,
+                percentiles: Percentiles::of(&durations),
+            })
+            .collect(),
+        failing_steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StepTrace, TaskInfo};
+    use chrono::{DateTime, Utc};
+
+    fn seconds(durations: &[i64]) -> Vec<TimeDelta> {
+        durations.iter().copied().map(TimeDelta::seconds).collect()
+    }
+
+    fn run(name: &str, status: &str, seconds: Option<i64>, failures: u32) -> DurableTrace {
+        let at = DateTime::<Utc>::from_timestamp(1_709_907_600, 0).unwrap();
+        let mut step = StepTrace::builder(
+            "charge",
+            at,
+            TaskInfo::builder("task", "charge", at)
+                .consecutive_failures(failures)
+                .build(),
+        );
+        let mut trace =
+            DurableTrace::builder(name, format!("{}-{:?}", name, seconds), at).status(status);
+        if let Some(seconds) = seconds {
+            step = step.completed_at(at + TimeDelta::seconds(seconds));
+            trace = trace.completed_at(at + TimeDelta::seconds(seconds));
+        }
+        trace.steps([step.build()]).build()
+    }
+
+    #[test]
+    fn percentile_of_one_sample() {
+        let sorted = seconds(&[7]);
+        for p in [0.0, 50.0, 99.0, 100.0] {
+            assert_eq!(percentile(&sorted, p), Some(TimeDelta::seconds(7)));
+        }
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn percentile_by_nearest_rank() {
+        let sorted = seconds(&(1..=20).collect::<Vec<_>>());
+        assert_eq!(percentile(&sorted, 0.0), Some(TimeDelta::seconds(1)));
+        assert_eq!(percentile(&sorted, 50.0), Some(TimeDelta::seconds(10)));
+        assert_eq!(percentile(&sorted, 95.0), Some(TimeDelta::seconds(19)));
+        assert_eq!(percentile(&sorted, 99.0), Some(TimeDelta::seconds(20)));
+
+        let percentiles = Percentiles::of(&seconds(&[20, 1, 10])).unwrap();
+        assert_eq!(percentiles.p50, TimeDelta::seconds(10));
+        assert_eq!(percentiles.max, TimeDelta::seconds(20));
+        assert_eq!(Percentiles::of(&[]), None);
+    }
+
+    #[test]
+    fn histogram_edges_belong_to_the_bucket_they_start() {
+        let histogram = histogram(&seconds(&[0, 5, 10, 15, 20]), 4);
+        let counts: Vec<_> = histogram.iter().map(|bucket| bucket.count).collect();
+        // 20s is the end of the last bucket, which includes it.
+        assert_eq!(counts, [1, 1, 1, 2]);
+        assert_eq!(histogram[1].start, TimeDelta::seconds(5));
+        assert_eq!(histogram[3].end, TimeDelta::seconds(20));
+    }
+
+    #[test]
+    fn histogram_last_bucket_reaches_the_maximum() {
+        let durations = vec![TimeDelta::zero(), TimeDelta::milliseconds(105)];
+        let histogram = histogram(&durations, 10);
+        assert_eq!(histogram[9].end, TimeDelta::milliseconds(105));
+        assert_eq!(histogram[9].count, 1);
+    }
+
+    #[test]
+    fn histogram_of_identical_durations() {
+        let buckets = histogram(&seconds(&[3, 3, 3]), 5);
+        assert_eq!(buckets.len(), 5);
+        assert_eq!(buckets[0].count, 3);
+        assert!(histogram(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn empty_fleet() {
+        assert_eq!(summarize(&[]), FleetSummary::default());
+    }
+
+    #[test]
+    fn summarizes_workflows_steps_and_failures() {
+        let traces = [
+            run("checkout", "completed", Some(10), 0),
+            run("checkout", "failed", Some(20), 2),
+            run("checkout", "running", None, 1),
+            run("refund", "completed", Some(5), 0),
+        ];
+        let summary = summarize(&traces);
+        assert_eq!(summary.traces, 4);
+        assert_eq!(
+            summary.by_status,
+            [
+                ("completed".to_string(), 2),
+                ("failed".to_string(), 1),
+                ("running".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            summary.by_name,
+            [("checkout".to_string(), 3), ("refund".to_string(), 1)]
+        );
+        let checkout = &summary.workflows[0];
+        assert_eq!((checkout.executions, checkout.completed), (3, 2));
+        assert_eq!(checkout.percentiles.unwrap().p50, TimeDelta::seconds(10));
+        assert_eq!(summary.steps[0].occurrences, 4);
+        assert_eq!(
+            summary.failing_steps,
+            [FailingStep {
+                durable_step_id: "charge".to_string(),
+                consecutive_failures: 3,
+                failed_occurrences: 2,
+            }]
+        );
+    }
+}
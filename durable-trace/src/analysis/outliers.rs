@@ -0,0 +1,256 @@
+//! Flags steps whose duration is unusual compared with earlier runs of the same workflow.
+
+use crate::analysis::fleet::{self, Bucket, Percentiles};
+use crate::analysis::{fine_duration, step_duration};
+use crate::{DurableTrace, StepTrace};
+use chrono::TimeDelta;
+use std::collections::HashMap;
+
+/// Earlier durations a step needs before it can be judged.
+pub const MIN_SAMPLES: usize = 5;
+/// Standard deviations from the mean beyond which a duration is an outlier.
+pub const Z_SCORE_THRESHOLD: f64 = 3.0;
+/// Buckets in a baseline's histogram.
+pub const HISTOGRAM_BUCKETS: usize = 12;
+
+/// Durations of one `durable_step_id` in earlier runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    /// Shortest first.
+    pub durations: Vec<TimeDelta>,
+    pub mean: TimeDelta,
+    pub std_dev: TimeDelta,
+    pub percentiles: Percentiles,
+    pub histogram: Vec<Bucket>,
+}
+
+impl Baseline {
+    /// `None` below [`MIN_SAMPLES`] durations.
+    pub fn new(mut durations: Vec<TimeDelta>) -> Option<Self> {
+        if durations.len() < MIN_SAMPLES {
+            return None;
+        }
+        durations.sort();
+        let millis: Vec<f64> = durations
+            .iter()
+            .map(|d| d.num_milliseconds() as f64)
+            .collect();
+        let mean = millis.iter().sum::<f64>() / millis.len() as f64;
+        let variance = millis.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / millis.len() as f64;
+        Some(Baseline {
+            mean: TimeDelta::milliseconds(mean.round() as i64),
+            std_dev: TimeDelta::milliseconds(variance.sqrt().round() as i64),
+            percentiles: Percentiles::of(&durations)?,
+            histogram: fleet::histogram(&durations, HISTOGRAM_BUCKETS),
+            durations,
+        })
+    }
+
+    /// Share of the baseline, 0-100, that was faster than `duration`.
+    pub fn percentile_rank(&self, duration: TimeDelta) -> f64 {
+        let faster = self.durations.partition_point(|d| *d < duration);
+        faster as f64 * 100.0 / self.durations.len() as f64
+    }
+
+    /// Infinite when every earlier run took the same time and `duration` differs from it.
+    pub fn z_score(&self, duration: TimeDelta) -> f64 {
+        let difference = (duration - self.mean).num_milliseconds() as f64;
+        match self.std_dev.num_milliseconds() {
+            0 if difference == 0.0 => 0.0,
+            0 => f64::INFINITY.copysign(difference),
+            std_dev => difference / std_dev as f64,
+        }
+    }
+
+    pub fn outlier(&self, duration: TimeDelta) -> Option<Outlier> {
+        let z_score = self.z_score(duration);
+        (z_score.abs() >= Z_SCORE_THRESHOLD).then(|| Outlier {
+            duration,
+            z_score,
+            percentile_rank: self.percentile_rank(duration),
+            baseline: self.clone(),
+        })
+    }
+}
+
+/// A step duration far from its [`Baseline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outlier {
+    pub duration: TimeDelta,
+    pub z_score: f64,
+    pub percentile_rank: f64,
+    pub baseline: Baseline,
+}
+
+impl Outlier {
+    pub fn is_slow(&self) -> bool {
+        self.z_score > 0.0
+    }
+
+    /// One sentence on how far off the duration is, for tooltips and detail panels.
+    pub fn explanation(&self) -> String {
+        let (direction, share) = if self.is_slow() {
+            ("slower", self.percentile_rank)
+        } else {
+            ("faster", 100.0 - self.percentile_rank)
+        };
+        if self.z_score.is_infinite() {
+            return format!(
+                "Took {}, while all {} earlier runs took {}.",
+                fine_duration(self.duration),
+                self.baseline.durations.len(),
+                fine_duration(self.baseline.mean),
+            );
+        }
+        format!(
+            "Took {}, {:.1} standard deviations from the mean of {} over {} earlier runs ({} than {:.0}% of them; p50 {}, p95 {}, p99 {}).",
+            fine_duration(self.duration),
+            self.z_score.abs(),
+            fine_duration(self.baseline.mean),
+            self.baseline.durations.len(),
+            direction,
+            share,
+            fine_duration(self.baseline.percentiles.p50),
+            fine_duration(self.baseline.percentiles.p95),
+            fine_duration(self.baseline.percentiles.p99),
+        )
+    }
+}
+
+/// Baselines for the steps of `trace`, from the runs in `traces` with the same `name` that were
+/// scheduled before it.
+pub fn baselines(trace: &DurableTrace, traces: &[DurableTrace]) -> HashMap<String, Baseline> {
+    let mut durations: HashMap<&str, Vec<TimeDelta>> = HashMap::new();
+    let earlier_runs = traces.iter().filter(|other| {
+        other.name == trace.name
+            && other.durable_execution_id != trace.durable_execution_id
+            && other.scheduled_at < trace.scheduled_at
+    });
+    for run in earlier_runs {
+        for step in &run.steps {
+            if let Some(duration) = step_duration(step) {
+                durations
+                    .entry(&step.durable_step_id)
+                    .or_default()
+                    .push(duration);
+            }
+        }
+    }
+    durations
+        .into_iter()
+        .filter_map(|(durable_step_id, durations)| {
+            Baseline::new(durations).map(|baseline| (durable_step_id.to_string(), baseline))
+        })
+        .collect()
+}
+
+/// Whether `step` is an outlier against its baseline, if it has completed and has one.
+pub fn assess(baselines: &HashMap<String, Baseline>, step: &StepTrace) -> Option<Outlier> {
+    baselines
+        .get(&step.durable_step_id)?
+        .outlier(step_duration(step)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaskInfo;
+    use chrono::{DateTime, Utc};
+
+    fn seconds(durations: &[i64]) -> Vec<TimeDelta> {
+        durations.iter().copied().map(TimeDelta::seconds).collect()
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_709_907_600 + seconds, 0).unwrap()
+    }
+
+    fn run(id: &str, scheduled: i64, step_seconds: i64) -> DurableTrace {
+        let step = StepTrace::builder(
+            "charge",
+            at(scheduled),
+            TaskInfo::builder("task", "charge", at(scheduled)).build(),
+        )
+        .completed_at(at(scheduled + step_seconds))
+        .build();
+        DurableTrace::builder("checkout", id, at(scheduled))
+            .steps([step])
+            .build()
+    }
+
+    #[test]
+    fn baseline_needs_min_samples() {
+        assert_eq!(Baseline::new(seconds(&[1; MIN_SAMPLES - 1])), None);
+        assert!(Baseline::new(seconds(&[1; MIN_SAMPLES])).is_some());
+    }
+
+    #[test]
+    fn baseline_sorts_and_measures_spread() {
+        let baseline = Baseline::new(seconds(&[9, 1, 5, 3, 7])).unwrap();
+        assert_eq!(baseline.durations, seconds(&[1, 3, 5, 7, 9]));
+        assert_eq!(baseline.mean, TimeDelta::seconds(5));
+        // Population standard deviation of 1, 3, 5, 7, 9 is sqrt(8).
+        assert_eq!(baseline.std_dev, TimeDelta::milliseconds(2828));
+        assert_eq!(baseline.percentiles.p50, TimeDelta::seconds(5));
+    }
+
+    #[test]
+    fn z_score_measures_standard_deviations() {
+        let baseline = Baseline::new(seconds(&[1, 3, 5, 7, 9])).unwrap();
+        assert_eq!(baseline.z_score(TimeDelta::seconds(5)), 0.0);
+        let z_score = baseline.z_score(TimeDelta::seconds(15));
+        assert!((z_score - 10_000.0 / 2828.0).abs() < 1e-9);
+        assert!(baseline.z_score(TimeDelta::seconds(1)) < 0.0);
+    }
+
+    #[test]
+    fn z_score_without_spread() {
+        let baseline = Baseline::new(seconds(&[2; 6])).unwrap();
+        assert_eq!(baseline.z_score(TimeDelta::seconds(2)), 0.0);
+        assert_eq!(baseline.z_score(TimeDelta::seconds(2000)), f64::INFINITY);
+        assert_eq!(baseline.z_score(TimeDelta::seconds(1)), f64::NEG_INFINITY);
+
+        assert_eq!(baseline.outlier(TimeDelta::seconds(2)), None);
+        let outlier = baseline.outlier(TimeDelta::seconds(2000)).unwrap();
+        assert!(outlier.is_slow());
+        assert_eq!(outlier.percentile_rank, 100.0);
+        assert!(outlier.explanation().contains("all 6 earlier runs took"));
+    }
+
+    #[test]
+    fn percentile_rank_counts_faster_runs() {
+        let baseline = Baseline::new(seconds(&[1, 2, 3, 4, 5])).unwrap();
+        assert_eq!(baseline.percentile_rank(TimeDelta::zero()), 0.0);
+        assert_eq!(baseline.percentile_rank(TimeDelta::seconds(3)), 40.0);
+        assert_eq!(baseline.percentile_rank(TimeDelta::seconds(6)), 100.0);
+    }
+
+    #[test]
+    fn assess_uses_earlier_runs_of_the_same_workflow() {
+        let mut earlier: Vec<DurableTrace> = (0..MIN_SAMPLES as i64)
+            .map(|run_index| {
+                run(
+                    &format!("run-{}", run_index),
+                    run_index * 100,
+                    10 + run_index % 2,
+                )
+            })
+            .collect();
+        // Neither a later run nor another workflow counts towards the baseline.
+        earlier.push(run("later", 10_000, 1));
+        let mut other = run("other", 0, 1);
+        other.name = "refund".to_string();
+        earlier.push(other);
+
+        let current = run("current", 1_000, 600);
+        let found = baselines(&current, &earlier);
+        assert_eq!(found["charge"].durations.len(), MIN_SAMPLES);
+        let outlier = assess(&found, &current.steps[0]).unwrap();
+        assert!(outlier.is_slow());
+        assert_eq!(assess(&found, &run("usual", 1_000, 10).steps[0]), None);
+
+        // Too few earlier runs: nothing to judge against.
+        let found = baselines(&current, &earlier[..MIN_SAMPLES - 1]);
+        assert_eq!(assess(&found, &current.steps[0]), None);
+    }
+}
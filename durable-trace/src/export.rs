@@ -0,0 +1,17 @@
+//! Renderers that turn a trace into formats other tools understand.
+
+pub mod chrome;
+pub mod csv;
+pub mod markdown;
+pub mod mermaid;
+pub mod otlp;
+pub mod svg;
+
+/// Whether exported times are wall-clock times or offsets from the start of the execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeBase {
+    #[default]
+    Absolute,
+    /// `T+HH:MM:SS.mmm` from `DurableTrace::scheduled_at`, so runs compare by shape.
+    Relative,
+}
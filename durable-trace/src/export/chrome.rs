@@ -0,0 +1,289 @@
+use crate::{analysis, DurableTrace, StepTrace, TaskInfo};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+const PID: u32 = 1;
+// Lane for the execution itself, task lanes start after it.
+const EXECUTION_TID: usize = 0;
+
+/// Converts `trace` into the Chrome Trace Event format understood by Perfetto and
+/// `chrome://tracing`.
+///
+/// Each task name gets its own thread lane, each step becomes a complete (`X`) event on the lane
+/// of its input task, and task failures, retries and annotations become instant (`i`) events.
+/// Timestamps are microseconds since the execution was scheduled.
+pub fn export(trace: &DurableTrace) -> Value {
+    let window_end =
+        analysis::find_max_completion(&trace.steps, trace.scheduled_at, trace.completed_at);
+    let micros = |at: DateTime<Utc>| (at - trace.scheduled_at).num_microseconds().unwrap_or(0);
+
+    let mut lanes: Vec<&str> = Vec::new();
+    for step in &trace.steps {
+        let tasks = [Some(&step.in_task_info), step.out_task_info.as_ref()];
+        for info in tasks.into_iter().flatten() {
+            if !lanes.contains(&info.task_name.as_str()) {
+                lanes.push(&info.task_name);
+            }
+        }
+    }
+    let lane = |task_name: &str| {
+        EXECUTION_TID
+            + 1
+            + lanes
+                .iter()
+                .position(|name| *name == task_name)
+                .unwrap_or(0)
+    };
+
+    let mut events = vec![
+        json!({"name": "process_name", "ph": "M", "pid": PID, "args": {"name": trace.name}}),
+        json!({"name": "thread_name", "ph": "M", "pid": PID, "tid": EXECUTION_TID, "args": {"name": "execution"}}),
+    ];
+    for (i, task_name) in lanes.iter().enumerate() {
+        events.push(json!({
+            "name": "thread_name", "ph": "M", "pid": PID, "tid": EXECUTION_TID + 1 + i,
+            "args": {"name": task_name},
+        }));
+    }
+
+    events.push(json!({
+        "name": trace.name,
+        "cat": "execution",
+        "ph": "X",
+        "pid": PID,
+        "tid": EXECUTION_TID,
+        "ts": 0,
+        "dur": micros(trace.completed_at.unwrap_or(window_end)),
+        "args": {
+            "durableExecutionId": trace.durable_execution_id,
+            "status": trace.status,
+            "version": trace.version,
+            "failureReason": trace.failure_reason,
+        },
+    }));
+
+    for step in &trace.steps {
+        let tid = lane(&step.in_task_info.task_name);
+        let end = step.completed_at.unwrap_or(window_end);
+        events.push(json!({
+            "name": step.durable_step_id,
+            "cat": if step.completed_at.is_some() { "step" } else { "step,running" },
+            "ph": "X",
+            "pid": PID,
+            "tid": tid,
+            "ts": micros(step.scheduled_at),
+            "dur": micros(end) - micros(step.scheduled_at),
+            "args": {
+                "result": step.result,
+                "completed": step.completed_at.is_some(),
+                "inTaskInfo": step.in_task_info,
+                "outTaskInfo": step.out_task_info,
+            },
+        }));
+        let tasks = [Some(&step.in_task_info), step.out_task_info.as_ref()];
+        for info in tasks.into_iter().flatten() {
+            events.extend(task_instants(step, info, lane(&info.task_name), &micros));
+        }
+    }
+
+    // Notes sit at the start of their step, or of the execution, on the matching lane.
+    for annotation in &trace.annotations {
+        let step = trace.steps.iter().find(|step| annotation.is_on_step(step));
+        let (tid, at) = match step {
+            Some(step) => (lane(&step.in_task_info.task_name), step.scheduled_at),
+            None => (EXECUTION_TID, trace.scheduled_at),
+        };
+        events.push(json!({
+            "name": annotation.text,
+            "cat": "annotation",
+            "ph": "i",
+            "s": "t",
+            "pid": PID,
+            "tid": tid,
+            "ts": micros(at),
+            "args": {
+                "durableStepId": annotation.durable_step_id,
+                "createdAt": annotation.created_at.to_rfc3339(),
+            },
+        }));
+    }
+
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "otherData": {
+            "durableExecutionId": trace.durable_execution_id,
+            "scheduledAt": trace.scheduled_at.to_rfc3339(),
+        },
+    })
+}
+
+/// [`export`] serialised as JSON.
+pub fn export_json(trace: &DurableTrace) -> String {
+    serde_json::to_string_pretty(&export(trace)).unwrap_or_default()
+}
+
+// A failure marker where the task last failed and a retry marker when it ran more than once.
+fn task_instants(
+    step: &StepTrace,
+    info: &TaskInfo,
+    tid: usize,
+    micros: &impl Fn(DateTime<Utc>) -> i64,
+) -> Vec<Value> {
+    let mut instants = Vec::new();
+    if info.consecutive_failures > 0 || info.last_failure.is_some() {
+        let at = info
+            .last_failure
+            .unwrap_or(step.completed_at.unwrap_or(info.execution_time));
+        instants.push(json!({
+            "name": format!("failure: {}", info.task_name),
+            "cat": "failure",
+            "ph": "i",
+            "s": "t",
+            "pid": PID,
+            "tid": tid,
+            "ts": micros(at),
+            "args": {
+                "durableStepId": step.durable_step_id,
+                "consecutiveFailures": info.consecutive_failures,
+            },
+        }));
+    }
+    if info.execution_version > 1 {
+        instants.push(json!({
+            "name": format!("retry: {} (attempt {})", info.task_name, info.execution_version),
+            "cat": "retry",
+            "ph": "i",
+            "s": "t",
+            "pid": PID,
+            "tid": tid,
+            "ts": micros(info.execution_time),
+            "args": {
+                "durableStepId": step.durable_step_id,
+                "executionVersion": info.execution_version,
+            },
+        }));
+    }
+    instants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_709_907_600 + seconds, 0).unwrap()
+    }
+
+    fn trace() -> DurableTrace {
+        let reserve = StepTrace::builder(
+            "reserve",
+            at(0),
+            TaskInfo::builder("task-1", "stock", at(0)).build(),
+        )
+        .completed_at(at(10))
+        .out_task_info(TaskInfo::builder("task-2", "notify", at(10)).build())
+        .build();
+        // Still running on its third attempt, after failing at 15s.
+        let charge = StepTrace::builder(
+            "charge",
+            at(5),
+            TaskInfo::builder("task-3", "payments", at(20))
+                .execution_version(3)
+                .consecutive_failures(2)
+                .last_failure(at(15))
+                .build(),
+        )
+        .build();
+        let restock = StepTrace::builder(
+            "restock",
+            at(12),
+            TaskInfo::builder("task-4", "stock", at(12)).build(),
+        )
+        .completed_at(at(30))
+        .build();
+        DurableTrace::builder("order", "exec-1", at(0))
+            .steps([reserve, charge, restock])
+            .build()
+    }
+
+    fn events(category: &str) -> Vec<Value> {
+        export(&trace())["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["cat"] == category)
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn one_lane_per_task_name() {
+        let exported = export(&trace());
+        let lanes: Vec<_> = exported["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["name"] == "thread_name")
+            .map(|event| {
+                (
+                    event["tid"].as_u64().unwrap(),
+                    event["args"]["name"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            lanes,
+            [
+                (0, json!("execution")),
+                (1, json!("stock")),
+                (2, json!("notify")),
+                (3, json!("payments")),
+            ]
+        );
+        let steps = events("step");
+        assert_eq!(steps[0]["tid"], 1);
+        assert_eq!(steps[1]["name"], "restock");
+        assert_eq!(steps[1]["tid"], 1);
+    }
+
+    #[test]
+    fn running_steps_reach_the_end_of_the_window() {
+        let [charge] = events("step,running").try_into().unwrap();
+        assert_eq!(charge["tid"], 3);
+        assert_eq!(charge["ts"], 5_000_000);
+        assert_eq!(charge["dur"], 25_000_000);
+        assert_eq!(charge["args"]["completed"], false);
+    }
+
+    #[test]
+    fn failures_and_retries_are_instants() {
+        let [failure] = events("failure").try_into().unwrap();
+        assert_eq!(failure["ph"], "i");
+        assert_eq!(failure["tid"], 3);
+        assert_eq!(failure["ts"], 15_000_000);
+        assert_eq!(failure["args"]["consecutiveFailures"], 2);
+
+        let [retry] = events("retry").try_into().unwrap();
+        assert_eq!(retry["name"], "retry: payments (attempt 3)");
+        assert_eq!(retry["ts"], 20_000_000);
+    }
+
+    #[test]
+    fn failures_without_a_time_sit_at_the_last_execution() {
+        let mut trace = trace();
+        trace.steps[1].in_task_info.last_failure = None;
+        let exported = export(&trace);
+        let failure = exported["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|event| event["cat"] == "failure")
+            .unwrap();
+        assert_eq!(
+            failure["ts"],
+            TimeDelta::seconds(20).num_microseconds().unwrap()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::analysis;
+use crate::StepTrace;
+use chrono::{DateTime, Utc};
+
+const COLUMNS: [&str; 11] = [
+    "durable_step_id",
+    "in_task_name",
+    "out_task_name",
+    "scheduled_at",
+    "completed_at",
+    "duration_seconds",
+    "in_consecutive_failures",
+    "out_consecutive_failures",
+    "in_execution_version",
+    "out_execution_version",
+    "result",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "csv",
+            Delimiter::Tab => "tsv",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "text/csv",
+            Delimiter::Tab => "text/tab-separated-values",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// RFC 3339 in UTC.
+    #[default]
+    Iso,
+    EpochSeconds,
+    EpochMillis,
+    /// Offsets from the given origin, see [`analysis::relative_time`].
+    Relative(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvOptions {
+    pub delimiter: Delimiter,
+    pub time_format: TimeFormat,
+}
+
+/// Renders one row per step, in the order given, with a header row first.
+pub fn render<'a>(steps: impl IntoIterator<Item = &'a StepTrace>, options: &CsvOptions) -> String {
+    let mut out = String::new();
+    push_row(&mut out, COLUMNS.iter().map(|c| c.to_string()), options);
+    for step in steps {
+        let out_task = step.out_task_info.as_ref();
+        let row = [
+            step.durable_step_id.clone(),
+            step.in_task_info.task_name.clone(),
+            text(out_task.map(|info| info.task_name.clone())),
+            format_time(step.scheduled_at, options.time_format),
+            text(
+                step.completed_at
+                    .map(|at| format_time(at, options.time_format)),
+            ),
+            text(analysis::step_duration(step).map(|d| d.num_seconds().to_string())),
+            step.in_task_info.consecutive_failures.to_string(),
+            text(out_task.map(|info| info.consecutive_failures.to_string())),
+            step.in_task_info.execution_version.to_string(),
+            text(out_task.map(|info| info.execution_version.to_string())),
+            text(step.result.clone()),
+        ];
+        push_row(&mut out, row.into_iter(), options);
+    }
+    out
+}
+
+fn push_row(out: &mut String, fields: impl Iterator<Item = String>, options: &CsvOptions) {
+    let separator = match options.delimiter {
+        Delimiter::Comma => ",",
+        Delimiter::Tab => "\t",
+    };
+    let fields = fields
+        .map(|field| escape(&field, options.delimiter))
+        .collect::<Vec<_>>();
+    out.push_str(&fields.join(separator));
+    out.push_str("\r\n");
+}
+
+// CSV quotes fields as in RFC 4180; TSV has no quoting, so separators are blanked instead.
+fn escape(field: &str, delimiter: Delimiter) -> String {
+    match delimiter {
+        Delimiter::Comma if field.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        }
+        Delimiter::Comma => field.to_string(),
+        Delimiter::Tab => field.replace(['\t', '\n', '\r'], " "),
+    }
+}
+
+fn format_time(at: DateTime<Utc>, time_format: TimeFormat) -> String {
+    match time_format {
+        TimeFormat::Iso => at.to_rfc3339(),
+        TimeFormat::EpochSeconds => at.timestamp().to_string(),
+        TimeFormat::EpochMillis => at.timestamp_millis().to_string(),
+        TimeFormat::Relative(origin) => analysis::relative_time(at, origin),
+    }
+}
+
+fn text(value: Option<String>) -> String {
+    value.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaskInfo;
+
+    fn step(result: &str) -> StepTrace {
+        let at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        StepTrace::builder(
+            "charge",
+            at,
+            TaskInfo::builder("task-1", "charge", at).build(),
+        )
+        .result(result)
+        .build()
+    }
+
+    fn row(result: &str, delimiter: Delimiter) -> String {
+        let options = CsvOptions {
+            delimiter,
+            ..CsvOptions::default()
+        };
+        let text = render([&step(result)], &options);
+        text.split_once("\r\n").unwrap().1.to_string()
+    }
+
+    #[test]
+    fn header_names_every_column() {
+        let text = render([], &CsvOptions::default());
+        assert_eq!(text, format!("{}\r\n", COLUMNS.join(",")));
+    }
+
+    #[test]
+    fn csv_quotes_delimiters_quotes_and_newlines() {
+        assert_eq!(escape("plain", Delimiter::Comma), "plain");
+        assert_eq!(escape("a,b", Delimiter::Comma), "\"a,b\"");
+        assert_eq!(escape("say \"hi\"", Delimiter::Comma), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines", Delimiter::Comma), "\"two\nlines\"");
+        assert_eq!(escape("a\tb", Delimiter::Comma), "a\tb");
+    }
+
+    #[test]
+    fn tsv_blanks_tabs_and_newlines() {
+        assert_eq!(escape("a\tb\r\nc", Delimiter::Tab), "a b  c");
+        assert_eq!(escape("a,\"b\"", Delimiter::Tab), "a,\"b\"");
+    }
+
+    #[test]
+    fn rows_escape_their_fields() {
+        assert!(row("{\"a\": 1, \"b\": 2}", Delimiter::Comma)
+            .ends_with(",\"{\"\"a\"\": 1, \"\"b\"\": 2}\"\r\n"));
+        let tsv = row("x\ty", Delimiter::Tab);
+        assert!(tsv.ends_with("\tx y\r\n"), "{:?}", tsv);
+        assert_eq!(tsv.matches('\t').count(), COLUMNS.len() - 1);
+    }
+}
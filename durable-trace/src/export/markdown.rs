@@ -0,0 +1,198 @@
+use crate::export::{mermaid, TimeBase};
+use crate::validation::{self, Severity};
+use crate::{analysis, DurableTrace};
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// Renders a Markdown report of `trace`: header fields, a step table, validation findings,
+/// annotations and the timeline as an embedded Mermaid diagram.
+pub fn report(trace: &DurableTrace) -> String {
+    report_with(trace, TimeBase::Absolute)
+}
+
+/// [`report`] with step and execution times written in `time_base`.
+pub fn report_with(trace: &DurableTrace, time_base: TimeBase) -> String {
+    let time = |at: DateTime<Utc>| match time_base {
+        TimeBase::Absolute => at.to_rfc3339(),
+        TimeBase::Relative => analysis::relative_time(at, trace.scheduled_at),
+    };
+    let optional_date = |at: Option<DateTime<Utc>>| at.map(time).unwrap_or_else(|| "-".to_string());
+    let mut out = String::new();
+    let _ = writeln!(out, "# Durable Trace: {}\n", cell(&trace.name));
+
+    let _ = writeln!(out, "| Field | Value |");
+    let _ = writeln!(out, "| --- | --- |");
+    let mut field = |name: &str, value: String| {
+        let _ = writeln!(out, "| {} | {} |", name, cell(&value));
+    };
+    field("Execution ID", trace.durable_execution_id.clone());
+    field("Status", trace.status.clone());
+    field("Version", trace.version.to_string());
+    field("Scheduled At", time(trace.scheduled_at));
+    field("Completed At", optional_date(trace.completed_at));
+    field(
+        "Total Duration",
+        analysis::trace_duration(trace)
+            .map(analysis::compact_duration)
+            .unwrap_or_else(|| "-".to_string()),
+    );
+    if let Some(failure_reason) = &trace.failure_reason {
+        field("Failure Reason", failure_reason.clone());
+    }
+    if let Some(failure_source) = &trace.failure_source {
+        field("Failure Source", failure_source.clone());
+    }
+
+    if let Some(payload) = &trace.payload {
+        let fence = fence(payload);
+        let _ = writeln!(out, "\n## Payload\n\n{}json\n{}\n{}", fence, payload, fence);
+    }
+    if let Some(result) = &trace.result {
+        let fence = fence(result);
+        let _ = writeln!(out, "\n## Result\n\n{}json\n{}\n{}", fence, result, fence);
+    }
+
+    let _ = writeln!(out, "\n## Steps\n");
+    let _ = writeln!(
+        out,
+        "| # | Step ID | Task | Scheduled At | Completed At | Duration | Result |"
+    );
+    let _ = writeln!(out, "| --- | --- | --- | --- | --- | --- | --- |");
+    for (i, step) in trace.steps.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            i + 1,
+            cell(&step.durable_step_id),
+            cell(&step.in_task_info.task_name),
+            time(step.scheduled_at),
+            optional_date(step.completed_at),
+            analysis::step_duration(step)
+                .map(analysis::compact_duration)
+                .unwrap_or_else(|| "Not completed".to_string()),
+            cell(step.result.as_deref().unwrap_or("-")),
+        );
+    }
+
+    let _ = writeln!(out, "\n## Validation Findings\n");
+    let findings = validation::validate(trace);
+    if findings.is_empty() {
+        let _ = writeln!(out, "No findings.");
+    }
+    for finding in findings {
+        let severity = match finding.severity {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        match finding.durable_step_id {
+            Some(step_id) => {
+                let _ = writeln!(
+                    out,
+                    "- **{}** {}: {}",
+                    severity,
+                    code(&step_id),
+                    finding.message
+                );
+            }
+            None => {
+                let _ = writeln!(out, "- **{}**: {}", severity, finding.message);
+            }
+        }
+    }
+
+    if !trace.annotations.is_empty() {
+        let _ = writeln!(out, "\n## Annotations\n");
+    }
+    for annotation in &trace.annotations {
+        let target = match &annotation.durable_step_id {
+            Some(step_id) => code(step_id),
+            None => "Execution".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "- **{}** ({}): {}",
+            target,
+            annotation.created_at.to_rfc3339(),
+            annotation.text.replace(['\r', '\n'], " ")
+        );
+    }
+
+    let gantt = mermaid::gantt_with(trace, time_base);
+    let fence = fence(&gantt);
+    let _ = writeln!(out, "\n## Timeline\n\n{}mermaid\n{}{}", fence, gantt, fence);
+    out
+}
+
+// Table cells cannot contain raw pipes or line breaks.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+// Backticks longer than any run of them in `text`, so the text cannot close a fence early.
+fn fence(text: &str) -> String {
+    "`".repeat(longest_backtick_run(text).max(2) + 1)
+}
+
+// Inline code showing `text` verbatim, padded so backticks at its ends stay part of it.
+fn code(text: &str) -> String {
+    match longest_backtick_run(text) {
+        0 => format!("`{}`", text),
+        longest => {
+            let ticks = "`".repeat(longest + 1);
+            format!("{} {} {}", ticks, text, ticks)
+        }
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StepTrace, TaskInfo};
+
+    fn trace() -> DurableTrace {
+        let at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        let step = StepTrace::builder("a|b", at, TaskInfo::builder("task-1", "charge", at).build())
+            .result("ok | done\nnext")
+            .build();
+        DurableTrace::builder("order", "exec-1", at)
+            .payload("{\"note\": \"```\\n# not a heading\"}\n```")
+            .result("{}")
+            .steps([step])
+            .build()
+    }
+
+    #[test]
+    fn escapes_pipes_in_table_cells() {
+        let report = report(&trace());
+        assert!(report.contains("| 1 | a\\|b | charge |"), "{}", report);
+        assert!(report.contains("| ok \\| done next |"), "{}", report);
+    }
+
+    #[test]
+    fn payload_fence_outlasts_backticks_inside() {
+        let report = report(&trace());
+        assert!(
+            report.contains(
+                "## Payload\n\n````json\n{\"note\": \"```\\n# not a heading\"}\n```\n````\n"
+            ),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains("## Result\n\n```json\n{}\n```\n"),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn inline_code_holds_backticks() {
+        assert_eq!(code("step-1"), "`step-1`");
+        assert_eq!(code("a`b"), "`` a`b ``");
+        assert_eq!(code("``"), "``` `` ```");
+    }
+}
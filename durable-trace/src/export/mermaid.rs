@@ -0,0 +1,163 @@
+use crate::analysis;
+use crate::export::TimeBase;
+use crate::DurableTrace;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::fmt::Write;
+
+// Mermaid parses dates with dayjs, so both sides use the same zone-less UTC layout.
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const MERMAID_DATE_FORMAT: &str = "YYYY-MM-DD HH:mm:ss";
+
+/// Renders the steps of `trace` as a Mermaid `gantt` diagram, one section per task name.
+///
+/// Steps still running are drawn up to the end of the trace window and marked active,
+/// steps whose task had failures are marked critical.
+pub fn gantt(trace: &DurableTrace) -> String {
+    gantt_with(trace, TimeBase::Absolute)
+}
+
+/// [`gantt`] in `time_base`. Relative diagrams start at the Unix epoch so the axis reads as
+/// hours, minutes and seconds since the execution was scheduled, with the day counted from 1
+/// once they span a day or more.
+pub fn gantt_with(trace: &DurableTrace, time_base: TimeBase) -> String {
+    let format_date = |at: DateTime<Utc>| match time_base {
+        TimeBase::Absolute => format_date(at),
+        TimeBase::Relative => format_date(DateTime::UNIX_EPOCH + (at - trace.scheduled_at)),
+    };
+    let window_end =
+        analysis::find_max_completion(&trace.steps, trace.scheduled_at, trace.completed_at);
+    // Hours wrap back to zero after a day, so longer relative axes need the day of the year too.
+    let axis_format = match time_base {
+        TimeBase::Absolute => "%H:%M",
+        TimeBase::Relative if window_end - trace.scheduled_at >= TimeDelta::days(1) => {
+            "day %-j %H:%M"
+        }
+        TimeBase::Relative => "%H:%M:%S",
+    };
+    let mut out = String::new();
+    let _ = writeln!(out, "gantt");
+    let _ = writeln!(out, "    title {}", sanitize(&trace.name));
+    let _ = writeln!(out, "    dateFormat {}", MERMAID_DATE_FORMAT);
+    let _ = writeln!(out, "    axisFormat {}", axis_format);
+
+    let mut sections: Vec<&str> = Vec::new();
+    for step in &trace.steps {
+        if !sections.contains(&step.in_task_info.task_name.as_str()) {
+            sections.push(&step.in_task_info.task_name);
+        }
+    }
+    for section in sections {
+        let _ = writeln!(out, "    section {}", sanitize(section));
+        let steps = trace
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| step.in_task_info.task_name == section);
+        for (i, step) in steps {
+            let failed = step.in_task_info.consecutive_failures > 0
+                || step
+                    .out_task_info
+                    .as_ref()
+                    .is_some_and(|info| info.consecutive_failures > 0);
+            let mut tags = Vec::new();
+            if failed {
+                tags.push("crit");
+            }
+            tags.push(if step.completed_at.is_some() {
+                "done"
+            } else {
+                "active"
+            });
+            let _ = writeln!(
+                out,
+                "    {} :{}, step{}, {}, {}",
+                sanitize(&step.durable_step_id),
+                tags.join(", "),
+                i,
+                format_date(step.scheduled_at),
+                format_date(step.completed_at.unwrap_or(window_end)),
+            );
+        }
+    }
+    out
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+// Colons, hashes and semicolons are part of the gantt syntax and cannot be escaped.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ':' | '#' | ';' | '\n' | '\r' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StepTrace, TaskInfo};
+
+    fn trace(step_hours: i64) -> DurableTrace {
+        let at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        let step = StepTrace::builder(
+            "charge: card #1; retry",
+            at,
+            TaskInfo::builder("task-1", "pay\nments", at)
+                .consecutive_failures(1)
+                .build(),
+        )
+        .completed_at(at + TimeDelta::hours(step_hours))
+        .build();
+        let running =
+            StepTrace::builder("ship", at, TaskInfo::builder("task-2", "ship", at).build()).build();
+        DurableTrace::builder("order: 42", "exec-1", at)
+            .steps([step, running])
+            .build()
+    }
+
+    #[test]
+    fn sanitizes_labels() {
+        assert_eq!(sanitize("a: b#c;d\r\ne"), "a  b c d  e");
+        let gantt = gantt(&trace(1));
+        assert!(gantt.contains("    title order  42\n"), "{}", gantt);
+        assert!(gantt.contains("    section pay ments\n"), "{}", gantt);
+        assert!(gantt.contains("    charge  card  1  retry :crit, done, step0, 2024-03-08 14:20:00, 2024-03-08 15:20:00\n"), "{}", gantt);
+        // Running steps reach the end of the window.
+        assert!(
+            gantt.contains("    ship :active, step1, 2024-03-08 14:20:00, 2024-03-08 15:20:00\n"),
+            "{}",
+            gantt
+        );
+    }
+
+    #[test]
+    fn relative_diagrams_start_at_the_epoch() {
+        let gantt = gantt_with(&trace(1), TimeBase::Relative);
+        assert!(gantt.contains("    axisFormat %H:%M:%S\n"), "{}", gantt);
+        assert!(
+            gantt.contains(", 1970-01-01 00:00:00, 1970-01-01 01:00:00\n"),
+            "{}",
+            gantt
+        );
+    }
+
+    #[test]
+    fn relative_axis_counts_days_past_a_day() {
+        let gantt = gantt_with(&trace(30), TimeBase::Relative);
+        assert!(
+            gantt.contains("    axisFormat day %-j %H:%M\n"),
+            "{}",
+            gantt
+        );
+        assert!(
+            gantt.contains(", 1970-01-01 00:00:00, 1970-01-02 06:00:00\n"),
+            "{}",
+            gantt
+        );
+        assert!(gantt_with(&trace(30), TimeBase::Absolute).contains("    axisFormat %H:%M\n"));
+    }
+}
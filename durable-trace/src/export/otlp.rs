@@ -0,0 +1,239 @@
+use crate::otlp::{
+    attributes, Event, ExportTraceServiceRequest, KeyValue, Resource, ResourceSpans, Scope,
+    ScopeSpans, Span, Status, ANNOTATION_EVENT, SPAN_KIND_INTERNAL, STATUS_ERROR, STATUS_OK,
+    STATUS_UNSET,
+};
+use crate::{Annotation, DurableTrace, TaskInfo};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+const SCOPE_NAME: &str = "durable-trace";
+
+/// Converts `trace` into an OTLP/JSON `ExportTraceServiceRequest` with one root span for the
+/// execution and a child span per step.
+///
+/// Trace and span ids are hashes of `durable_execution_id` and `durable_step_id`, so exporting the
+/// same execution twice yields the same ids and backends deduplicate or update instead of
+/// creating new traces.
+pub fn export(trace: &DurableTrace) -> ExportTraceServiceRequest {
+    let trace_id = trace_id(&trace.durable_execution_id);
+    let root_span_id = span_id(&[&trace.durable_execution_id]);
+
+    let (status_code, message) = match trace.status.as_str() {
+        "failed" => (
+            STATUS_ERROR,
+            trace.failure_reason.clone().unwrap_or_default(),
+        ),
+        "completed" => (STATUS_OK, String::new()),
+        _ => (STATUS_UNSET, String::new()),
+    };
+    let mut root_attributes = vec![
+        KeyValue::string(attributes::EXECUTION_ID, &trace.durable_execution_id),
+        KeyValue::int(attributes::EXECUTION_VERSION, trace.version.into()),
+    ];
+    let optional = [
+        (attributes::PAYLOAD, &trace.payload),
+        (attributes::RESULT, &trace.result),
+        (attributes::FAILURE_SOURCE, &trace.failure_source),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            root_attributes.push(KeyValue::string(key, value));
+        }
+    }
+    let mut spans = vec![Span {
+        trace_id: trace_id.clone(),
+        span_id: root_span_id.clone(),
+        parent_span_id: String::new(),
+        name: trace.name.clone(),
+        kind: SPAN_KIND_INTERNAL,
+        start_time_unix_nano: nanos(trace.scheduled_at),
+        end_time_unix_nano: trace.completed_at.map(nanos).unwrap_or_default(),
+        attributes: root_attributes,
+        events: trace
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.durable_step_id.is_none())
+            .map(|annotation| annotation_event(annotation, trace.scheduled_at))
+            .collect(),
+        status: Status {
+            code: status_code,
+            message,
+        },
+    }];
+
+    // Repeated step ids get an occurrence number mixed in so their span ids stay distinct.
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for step in &trace.steps {
+        let occurrence = occurrences.entry(&step.durable_step_id).or_default();
+        let step_span_id = match *occurrence {
+            0 => span_id(&[&trace.durable_execution_id, &step.durable_step_id]),
+            n => span_id(&[
+                &trace.durable_execution_id,
+                &step.durable_step_id,
+                &n.to_string(),
+            ]),
+        };
+        *occurrence += 1;
+
+        let failures = step.in_task_info.consecutive_failures.saturating_add(
+            step.out_task_info
+                .as_ref()
+                .map(|info| info.consecutive_failures)
+                .unwrap_or_default(),
+        );
+        let status = if failures > 0 {
+            Status {
+                code: STATUS_ERROR,
+                message: format!("{} consecutive failures", failures),
+            }
+        } else if step.completed_at.is_some() {
+            Status {
+                code: STATUS_OK,
+                message: String::new(),
+            }
+        } else {
+            Status::default()
+        };
+
+        let mut step_attributes =
+            vec![KeyValue::string(attributes::STEP_ID, &step.durable_step_id)];
+        step_attributes.extend(task_attributes(
+            &step.in_task_info,
+            [
+                attributes::TASK_ID,
+                attributes::TASK_NAME,
+                attributes::TASK_CONSECUTIVE_FAILURES,
+                attributes::TASK_EXECUTION_VERSION,
+            ],
+        ));
+        if let Some(out_task_info) = &step.out_task_info {
+            step_attributes.extend(task_attributes(
+                out_task_info,
+                [
+                    attributes::OUT_TASK_ID,
+                    attributes::OUT_TASK_NAME,
+                    attributes::OUT_TASK_CONSECUTIVE_FAILURES,
+                    attributes::OUT_TASK_EXECUTION_VERSION,
+                ],
+            ));
+        }
+        if let Some(result) = &step.result {
+            step_attributes.push(KeyValue::string(attributes::RESULT, result));
+        }
+
+        let mut events: Vec<Event> = [Some(&step.in_task_info), step.out_task_info.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|info| {
+                info.last_failure.map(|last_failure| Event {
+                    time_unix_nano: nanos(last_failure),
+                    name: "task.failure".to_string(),
+                    attributes: vec![KeyValue::string(attributes::TASK_NAME, &info.task_name)],
+                })
+            })
+            .collect();
+        // Annotations go on the first span of a repeated step id so they import back once.
+        if *occurrence == 1 {
+            events.extend(
+                trace
+                    .annotations
+                    .iter()
+                    .filter(|annotation| annotation.is_on_step(step))
+                    .map(|annotation| annotation_event(annotation, step.scheduled_at)),
+            );
+        }
+
+        spans.push(Span {
+            trace_id: trace_id.clone(),
+            span_id: step_span_id,
+            parent_span_id: root_span_id.clone(),
+            name: step.durable_step_id.clone(),
+            kind: SPAN_KIND_INTERNAL,
+            start_time_unix_nano: nanos(step.scheduled_at),
+            end_time_unix_nano: step.completed_at.map(nanos).unwrap_or_default(),
+            attributes: step_attributes,
+            events,
+            status,
+        });
+    }
+
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Resource {
+                attributes: vec![KeyValue::string("service.name", &trace.name)],
+            },
+            scope_spans: vec![ScopeSpans {
+                scope: Scope {
+                    name: SCOPE_NAME.to_string(),
+                    version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                },
+                spans,
+            }],
+        }],
+    }
+}
+
+/// [`export`] serialised as pretty-printed OTLP/JSON.
+pub fn export_json(trace: &DurableTrace) -> String {
+    serde_json::to_string_pretty(&export(trace)).unwrap_or_default()
+}
+
+// Placed at the start of its span; when the note was written is an attribute.
+fn annotation_event(annotation: &Annotation, at: DateTime<Utc>) -> Event {
+    Event {
+        time_unix_nano: nanos(at),
+        name: ANNOTATION_EVENT.to_string(),
+        attributes: vec![
+            KeyValue::string(attributes::ANNOTATION_TEXT, &annotation.text),
+            KeyValue::string(
+                attributes::ANNOTATION_CREATED_AT,
+                annotation.created_at.to_rfc3339(),
+            ),
+        ],
+    }
+}
+
+// Keys are id, name, consecutive failures and execution version, in that order.
+fn task_attributes(info: &TaskInfo, keys: [&str; 4]) -> [KeyValue; 4] {
+    [
+        KeyValue::string(keys[0], &info.id),
+        KeyValue::string(keys[1], &info.task_name),
+        KeyValue::int(keys[2], info.consecutive_failures.into()),
+        KeyValue::int(keys[3], info.execution_version.into()),
+    ]
+}
+
+fn nanos(at: DateTime<Utc>) -> u64 {
+    at.timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+}
+
+// 16 byte trace id as 32 lowercase hex characters.
+fn trace_id(durable_execution_id: &str) -> String {
+    format!(
+        "{:016x}{:016x}",
+        fnv1a(&["trace", durable_execution_id]),
+        fnv1a(&["trace-low", durable_execution_id])
+    )
+}
+
+// 8 byte span id as 16 lowercase hex characters.
+fn span_id(parts: &[&str]) -> String {
+    format!("{:016x}", fnv1a(parts))
+}
+
+// FNV-1a is stable across platforms and releases, unlike `std`'s hashers.
+fn fnv1a(parts: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET_BASIS;
+    for (i, part) in parts.iter().enumerate() {
+        // A separator keeps ["ab", "c"] and ["a", "bc"] apart.
+        let separator = (i > 0).then_some(0xffu8);
+        for byte in separator.into_iter().chain(part.bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
@@ -0,0 +1,263 @@
+use crate::analysis;
+use crate::export::TimeBase;
+use crate::StepTrace;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+const LABEL_WIDTH: f64 = 220.0;
+const ROW_HEIGHT: f64 = 32.0;
+const BAR_HEIGHT: f64 = 18.0;
+const HEADER_HEIGHT: f64 = 56.0;
+const PADDING: f64 = 16.0;
+const TICKS: usize = 5;
+
+/// Colours of the drawing, as any CSS colour. The default matches the light theme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgPalette {
+    pub background: String,
+    pub completed: String,
+    pub running: String,
+    pub track: String,
+    pub grid: String,
+    pub text: String,
+    pub muted: String,
+}
+
+impl Default for SvgPalette {
+    // Same colours as the Tailwind classes used by the timeline.
+    fn default() -> Self {
+        SvgPalette {
+            background: "#ffffff".to_string(),
+            completed: "#2563eb".to_string(),
+            running: "#93c5fd".to_string(),
+            track: "#f3f4f6".to_string(),
+            grid: "#e5e7eb".to_string(),
+            text: "#111827".to_string(),
+            muted: "#6b7280".to_string(),
+        }
+    }
+}
+
+/// What to draw and how wide the bar area should be.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    pub title: String,
+    /// Width in pixels of the area bars are drawn in, already scaled by any zoom.
+    pub chart_width: f64,
+    /// Axis labels as wall-clock times or offsets from the start of the window.
+    pub time_base: TimeBase,
+    pub palette: SvgPalette,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            title: String::new(),
+            chart_width: 800.0,
+            time_base: TimeBase::Absolute,
+            palette: SvgPalette::default(),
+        }
+    }
+}
+
+/// Pixel size of the document [`render`] produces for `step_count` rows.
+pub fn size(step_count: usize, options: &SvgOptions) -> (f64, f64) {
+    let width = PADDING + LABEL_WIDTH + options.chart_width + PADDING;
+    let height = HEADER_HEIGHT + ROW_HEIGHT * step_count as f64 + PADDING;
+    (width, height)
+}
+
+/// Renders `steps` as a standalone SVG timeline over `window_start..window_end`.
+pub fn render(
+    steps: &[StepTrace],
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    options: &SvgOptions,
+) -> String {
+    let chart_left = PADDING + LABEL_WIDTH;
+    let (width, height) = size(steps.len(), options);
+    let palette = &options.palette;
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="ui-sans-serif, system-ui, sans-serif">"#,
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        escape(&palette.background)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="16" font-weight="600" fill="{}">{}</text>"#,
+        PADDING,
+        PADDING + 6.0,
+        escape(&palette.text),
+        escape(&options.title)
+    );
+
+    // Axis with evenly spaced ticks across the window.
+    let axis_y = HEADER_HEIGHT - 8.0;
+    let window = window_end - window_start;
+    for i in 0..=TICKS {
+        let fraction = i as f64 / TICKS as f64;
+        let x = chart_left + options.chart_width * fraction;
+        let at = window_start + window * i as i32 / TICKS as i32;
+        let label = match options.time_base {
+            TimeBase::Absolute => at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            TimeBase::Relative => analysis::relative_time(at, window_start),
+        };
+        let anchor = match i {
+            0 => "start",
+            TICKS => "end",
+            _ => "middle",
+        };
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="{}" stroke-dasharray="2 4"/>"#,
+            axis_y,
+            height - PADDING,
+            escape(&palette.grid),
+            x = x
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="11" text-anchor="{}" fill="{}">{}</text>"#,
+            x,
+            axis_y - 6.0,
+            anchor,
+            escape(&palette.muted),
+            label
+        );
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        let row_y = HEADER_HEIGHT + ROW_HEIGHT * i as f64;
+        let bar_y = row_y + (ROW_HEIGHT - BAR_HEIGHT) / 2.0;
+        let position = analysis::bar_position(step, window_start, window_end);
+        let (colour, duration) = match analysis::step_duration(step) {
+            Some(duration) => (&palette.completed, analysis::compact_duration(duration)),
+            None => (&palette.running, "Not completed".to_string()),
+        };
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="12" fill="{}">{}<tspan fill="{}" font-size="10"> {}</tspan></text>"#,
+            PADDING,
+            bar_y + BAR_HEIGHT - 5.0,
+            escape(&palette.text),
+            escape(&step.durable_step_id),
+            escape(&palette.muted),
+            escape(&duration)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="{}"/>"#,
+            chart_left,
+            bar_y,
+            options.chart_width,
+            BAR_HEIGHT,
+            escape(&palette.track)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"><title>{}</title></rect>"#,
+            chart_left + options.chart_width * position.left / 100.0,
+            bar_y,
+            (options.chart_width * position.width / 100.0).max(1.0),
+            BAR_HEIGHT,
+            escape(colour),
+            escape(&step.durable_step_id)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaskInfo;
+    use chrono::TimeDelta;
+
+    fn steps() -> (Vec<StepTrace>, DateTime<Utc>) {
+        let at = DateTime::from_timestamp(1_709_907_600, 0).unwrap();
+        let done = StepTrace::builder(
+            "a<b & \"c\"",
+            at,
+            TaskInfo::builder("task-1", "charge", at).build(),
+        )
+        .completed_at(at + TimeDelta::seconds(30))
+        .build();
+        let running =
+            StepTrace::builder("ship", at, TaskInfo::builder("task-2", "ship", at).build()).build();
+        (vec![done, running], at)
+    }
+
+    #[test]
+    fn sized_by_rows_and_chart_width() {
+        let options = SvgOptions::default();
+        assert_eq!(
+            size(2, &options),
+            (
+                2.0 * PADDING + LABEL_WIDTH + 800.0,
+                HEADER_HEIGHT + 2.0 * ROW_HEIGHT + PADDING
+            )
+        );
+    }
+
+    #[test]
+    fn escapes_step_ids() {
+        let (steps, at) = steps();
+        let svg = render(
+            &steps,
+            at,
+            at + TimeDelta::minutes(1),
+            &SvgOptions::default(),
+        );
+        assert!(
+            svg.contains("<title>a&lt;b &amp; &quot;c&quot;</title>"),
+            "{}",
+            svg
+        );
+        assert!(!svg.contains("a<b"));
+    }
+
+    #[test]
+    fn draws_with_the_given_palette() {
+        let (steps, at) = steps();
+        let palette = SvgPalette {
+            background: "rgb(17 24 39)".to_string(),
+            completed: "rgb(1 2 3)".to_string(),
+            running: "rgb(4 5 6)".to_string(),
+            ..SvgPalette::default()
+        };
+        let options = SvgOptions {
+            palette,
+            ..SvgOptions::default()
+        };
+        let svg = render(&steps, at, at + TimeDelta::minutes(1), &options);
+        assert!(svg.contains(r#"<rect width="100%" height="100%" fill="rgb(17 24 39)"/>"#));
+        assert!(
+            svg.contains(r#"fill="rgb(1 2 3)"><title>a&lt;b"#),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains(r#"fill="rgb(4 5 6)"><title>ship</title>"#),
+            "{}",
+            svg
+        );
+        assert!(!svg.contains("#ffffff"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Converters from other tracing formats into a [`DurableTrace`], with format detection.
+
+use crate::{migration, DurableTrace, TraceError};
+use serde_json::Value;
+
+pub mod bundle;
+pub mod markers;
+pub mod otlp;
+mod payloads;
+pub mod step_functions;
+pub mod temporal;
+
+/// The layouts a pasted or uploaded document can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    Durable,
+    Otlp,
+    Temporal,
+    StepFunctions,
+}
+
+impl TraceFormat {
+    pub const ALL: [TraceFormat; 4] = [
+        TraceFormat::Durable,
+        TraceFormat::Otlp,
+        TraceFormat::Temporal,
+        TraceFormat::StepFunctions,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TraceFormat::Durable => "Durable trace",
+            TraceFormat::Otlp => "OpenTelemetry (OTLP/JSON)",
+            TraceFormat::Temporal => "Temporal event history",
+            TraceFormat::StepFunctions => "AWS Step Functions history",
+        }
+    }
+
+    /// Stable identifier, used for form values and command-line flags.
+    pub fn key(&self) -> &'static str {
+        match self {
+            TraceFormat::Durable => "durable",
+            TraceFormat::Otlp => "otlp",
+            TraceFormat::Temporal => "temporal",
+            TraceFormat::StepFunctions => "step-functions",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        TraceFormat::ALL
+            .into_iter()
+            .find(|format| format.key() == key)
+    }
+}
+
+/// Guesses the format of `value` from its top-level keys, defaulting to a durable trace.
+pub fn detect_format(value: &Value) -> TraceFormat {
+    if value.get("resourceSpans").is_some() || value.get("resource_spans").is_some() {
+        TraceFormat::Otlp
+    } else if temporal::detect(value) {
+        TraceFormat::Temporal
+    } else if step_functions::detect(value) {
+        TraceFormat::StepFunctions
+    } else {
+        TraceFormat::Durable
+    }
+}
+
+/// Parses `json` as `format`, or as whatever [`detect_format`] decides when `None`.
+pub fn import(json: &str, format: Option<TraceFormat>) -> Result<DurableTrace, TraceError> {
+    import_value(serde_json::from_str(json)?, format)
+}
+
+/// [`import`] for a document that has already been parsed.
+pub fn import_value(value: Value, format: Option<TraceFormat>) -> Result<DurableTrace, TraceError> {
+    let format = format.unwrap_or_else(|| detect_format(&value));
+    match format {
+        TraceFormat::Durable => migration::parse_value(value),
+        TraceFormat::Otlp => otlp::import(value),
+        TraceFormat::Temporal => temporal::import(value),
+        TraceFormat::StepFunctions => step_functions::import(value),
+    }
+}
+
+// An error from the importer for `format`, such as "Temporal".
+pub(crate) fn import_error(format: &'static str, reason: &str) -> TraceError {
+    TraceError::Import {
+        format,
+        reason: reason.to_string(),
+    }
+}
+
+// Ids and counters are often int64, which exporters write as strings or numbers.
+pub(crate) fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_each_format() {
+        let documents = [
+            (fixtures::OTLP_COLLECTOR, TraceFormat::Otlp),
+            (fixtures::TEMPORAL_HISTORY, TraceFormat::Temporal),
+            (fixtures::STEP_FUNCTIONS_HISTORY, TraceFormat::StepFunctions),
+            // Anything unrecognised is read as a durable trace.
+            (r#"{"name": "order", "steps": []}"#, TraceFormat::Durable),
+        ];
+        for (fixture, format) in documents {
+            let value = serde_json::from_str(fixture).unwrap();
+            assert_eq!(detect_format(&value), format);
+        }
+    }
+}
+
+// Documents written by the tools each importer reads, shared by their tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::DurableTrace;
+
+    pub(crate) const OTLP_COLLECTOR: &str = include_str!("import/fixtures/otlp_collector.json");
+    pub(crate) const TEMPORAL_HISTORY: &str = include_str!("import/fixtures/temporal_history.json");
+    pub(crate) const STEP_FUNCTIONS_HISTORY: &str =
+        include_str!("import/fixtures/step_functions_history.json");
+
+    pub(crate) fn import(fixture: &str) -> DurableTrace {
+        super::import(fixture, None).expect("fixture imports")
+    }
+}
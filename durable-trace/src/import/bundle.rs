@@ -0,0 +1,142 @@
+//! Documents holding many traces: NDJSON with one trace per line, or a JSON array of traces.
+
+use crate::import::{import_value, TraceFormat};
+use crate::{DurableTrace, TraceError};
+use serde_json::Value;
+use std::fmt;
+
+/// Where in a bundle an entry came from, counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Line(usize),
+    Entry(usize),
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Line(line) => write!(f, "line {}", line),
+            Position::Entry(entry) => write!(f, "entry {}", entry),
+        }
+    }
+}
+
+/// An entry of a bundle that could not be imported.
+#[derive(Debug)]
+pub struct BundleFailure {
+    pub position: Position,
+    pub error: TraceError,
+}
+
+impl fmt::Display for BundleFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.error)
+    }
+}
+
+/// The traces of a bundle that imported, next to the entries that did not.
+#[derive(Debug, Default)]
+pub struct Bundle {
+    pub traces: Vec<DurableTrace>,
+    pub failures: Vec<BundleFailure>,
+}
+
+/// Imports every trace in `text`, which may be a single document, a JSON array of documents or
+/// NDJSON. A broken entry is reported in [`Bundle::failures`] without affecting the others.
+///
+/// Each entry is imported as `format`, or detected on its own when `None`.
+pub fn import(text: &str, format: Option<TraceFormat>) -> Bundle {
+    let mut bundle = Bundle::default();
+    let mut add = |position, result: Result<DurableTrace, TraceError>| match result {
+        Ok(trace) => bundle.traces.push(trace),
+        Err(error) => bundle.failures.push(BundleFailure { position, error }),
+    };
+
+    match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(entries)) => {
+            for (i, entry) in entries.into_iter().enumerate() {
+                add(Position::Entry(i + 1), import_value(entry, format));
+            }
+        }
+        Ok(value) => add(Position::Line(1), import_value(value, format)),
+        // Not one document, so NDJSON if any of its lines is a complete one. Blank lines are
+        // allowed between traces.
+        Err(_) if looks_line_delimited(text) => {
+            for (i, line) in text.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let result = serde_json::from_str(line)
+                    .map_err(TraceError::from)
+                    .and_then(|value| import_value(value, format));
+                add(Position::Line(i + 1), result);
+            }
+        }
+        // A single document with a mistake in it; its own error says where.
+        Err(e) => add(Position::Line(1), Err(e.into())),
+    }
+    bundle
+}
+
+// Lines of a pretty-printed document can be complete values too, like `"a"` in an array, but
+// only NDJSON has objects on a line of their own.
+fn looks_line_delimited(text: &str) -> bool {
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    let first = lines.next();
+    lines.peek().is_some()
+        && first
+            .into_iter()
+            .chain(lines)
+            .any(|line| matches!(serde_json::from_str(line), Ok(Value::Object(_))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(id: &str) -> String {
+        format!(
+            r#"{{"name": "order", "durableExecutionId": "{}", "scheduledAt": "2024-03-08T14:20:00Z", "status": "running", "version": 1, "steps": []}}"#,
+            id
+        )
+    }
+
+    #[test]
+    fn ndjson_keeps_good_lines() {
+        let text = format!("{}\n\n{{broken\n{}\n", trace("a"), trace("b"));
+        let bundle = import(&text, None);
+        assert_eq!(bundle.traces.len(), 2);
+        assert_eq!(bundle.failures.len(), 1);
+        assert_eq!(bundle.failures[0].position, Position::Line(3));
+    }
+
+    #[test]
+    fn ndjson_starting_with_a_broken_line() {
+        let text = format!("{{broken\n{}\n{}\n", trace("a"), trace("b"));
+        let bundle = import(&text, None);
+        assert_eq!(bundle.traces.len(), 2);
+        assert_eq!(bundle.failures.len(), 1);
+        assert_eq!(bundle.failures[0].position, Position::Line(1));
+    }
+
+    #[test]
+    fn array_entries() {
+        let text = format!("[{}, {{}}]", trace("a"));
+        let bundle = import(&text, None);
+        assert_eq!(bundle.traces.len(), 1);
+        assert_eq!(bundle.failures[0].position, Position::Entry(2));
+    }
+
+    #[test]
+    fn broken_pretty_document_is_one_failure() {
+        let text = "{\n  \"name\": \"order\",\n  \"status\": \"running\"\n  \"version\": 1\n}\n";
+        let bundle = import(text, None);
+        assert!(bundle.traces.is_empty());
+        assert_eq!(bundle.failures.len(), 1);
+        let message = bundle.failures[0].to_string();
+        assert!(message.contains("line 4"), "{}", message);
+    }
+}
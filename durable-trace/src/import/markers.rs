@@ -0,0 +1,93 @@
+//! Lists of external events to overlay on a trace's timeline.
+
+use crate::import::import_error;
+use crate::{Marker, TraceError};
+use serde_json::Value;
+
+const FORMAT: &str = "Markers";
+
+// Other names for marker fields accepted on import, and the field they stand for. Traces only
+// ever store the canonical names, so the schema does not need to know about these.
+const ALIASES: [(&str, &str); 3] = [("title", "name"), ("at", "start"), ("time", "start")];
+
+/// Parses a JSON array of markers, or an object with a `markers` array, such as
+/// `[{"name": "deploy v42", "at": "2024-03-08T14:20:00Z", "kind": "deploy"}]`.
+///
+/// Ranges have a `start` and an `end`; `at` and `time` are accepted for `start`, `title` for
+/// `name`.
+pub fn parse(json: &str) -> Result<Vec<Marker>, TraceError> {
+    let mut list = match serde_json::from_str(json)? {
+        Value::Object(mut wrapper) => wrapper.remove("markers").unwrap_or_default(),
+        list => list,
+    };
+    let Value::Array(entries) = &mut list else {
+        return Err(import_error(
+            FORMAT,
+            "expected an array of markers or an object with a `markers` array",
+        ));
+    };
+    for marker in entries.iter_mut().filter_map(Value::as_object_mut) {
+        for (alias, field) in ALIASES {
+            if marker.contains_key(field) {
+                continue;
+            }
+            if let Some(value) = marker.remove(alias) {
+                marker.insert(field.to_string(), value);
+            }
+        }
+    }
+    let markers: Vec<Marker> = serde_json::from_value(list)?;
+    if let Some(marker) = markers
+        .iter()
+        .find(|marker| marker.end.is_some_and(|end| end < marker.start))
+    {
+        return Err(import_error(
+            FORMAT,
+            &format!("marker \"{}\" ends before it starts", marker.name),
+        ));
+    }
+    Ok(markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_trace;
+
+    #[test]
+    fn aliases_become_canonical_fields() {
+        let markers = parse(
+            r#"{"markers": [
+                {"title": "deploy v42", "at": "2024-03-08T14:20:00Z", "kind": "deploy"},
+                {"name": "outage", "time": "2024-03-08T15:00:00Z", "end": "2024-03-08T15:30:00Z"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(markers[0].name, "deploy v42");
+        assert_eq!(
+            markers[1].end.unwrap().to_rfc3339(),
+            "2024-03-08T15:30:00+00:00"
+        );
+        assert!(serde_json::to_string(&markers)
+            .unwrap()
+            .contains("\"start\""));
+    }
+
+    #[test]
+    fn range_ending_before_start_is_rejected() {
+        let result = parse(
+            r#"[{"name": "x", "start": "2024-03-08T15:00:00Z", "end": "2024-03-08T14:00:00Z"}]"#,
+        );
+        assert!(matches!(result, Err(TraceError::Import { .. })));
+    }
+
+    #[test]
+    fn trace_loader_agrees_with_schema() {
+        let trace = r#"{"name": "order", "durableExecutionId": "a", "scheduledAt": "2024-03-08T14:20:00Z",
+            "status": "running", "version": 1, "steps": [],
+            "markers": [{"name": "deploy", "start": "2024-03-08T14:30:00Z"}]}"#;
+        assert_eq!(parse_trace(trace).unwrap().markers.len(), 1);
+        let aliased = trace.replace("\"start\"", "\"at\"");
+        assert!(parse_trace(&aliased).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::import::import_error;
+use crate::otlp::{attributes, ExportTraceServiceRequest, Span, ANNOTATION_EVENT, STATUS_ERROR};
+use crate::{Annotation, DurableTrace, StepTrace, TaskInfo, TraceError};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+const FORMAT: &str = "OTLP";
+
+/// Converts an OTLP/JSON `ExportTraceServiceRequest` into a [`DurableTrace`].
+///
+/// The first root span becomes the execution and every other span of the same trace becomes a
+/// step, ordered by start time. The `durable.*` attributes in [`crate::otlp::attributes`] take
+/// precedence over the span's own fields.
+pub fn import(value: Value) -> Result<DurableTrace, TraceError> {
+    let request: ExportTraceServiceRequest = serde_json::from_value(value)?;
+    let spans: Vec<Span> = request
+        .resource_spans
+        .into_iter()
+        .flat_map(|resource_spans| resource_spans.scope_spans)
+        .flat_map(|scope_spans| scope_spans.spans)
+        .collect();
+
+    let root = spans
+        .iter()
+        .filter(|span| {
+            span.parent_span_id.is_empty()
+                || !spans
+                    .iter()
+                    .any(|parent| parent.span_id == span.parent_span_id)
+        })
+        .min_by_key(|span| span.start_time_unix_nano)
+        .ok_or_else(|| import_error(FORMAT, "no spans found"))?;
+
+    let mut children: Vec<&Span> = spans
+        .iter()
+        .filter(|span| span.trace_id == root.trace_id && span.span_id != root.span_id)
+        .collect();
+    children.sort_by_key(|span| span.start_time_unix_nano);
+
+    let scheduled_at = timestamp(root.start_time_unix_nano)?;
+    let completed_at = end_timestamp(root)?;
+    let failed = root.status.code == STATUS_ERROR;
+    let status = match (failed, completed_at) {
+        (true, _) => "failed",
+        (false, Some(_)) => "completed",
+        (false, None) => "running",
+    };
+
+    let mut builder = DurableTrace::builder(
+        root.name.clone(),
+        root.string_attribute(attributes::EXECUTION_ID)
+            .unwrap_or_else(|| root.trace_id.clone()),
+        scheduled_at,
+    )
+    .status(status)
+    .version(count(root, attributes::EXECUTION_VERSION).unwrap_or(1));
+    if let Some(completed_at) = completed_at {
+        builder = builder.completed_at(completed_at);
+    }
+    if let Some(payload) = root.string_attribute(attributes::PAYLOAD) {
+        builder = builder.payload(payload);
+    }
+    if let Some(result) = root.string_attribute(attributes::RESULT) {
+        builder = builder.result(result);
+    }
+    if failed {
+        builder = builder.is_error(true);
+        if !root.status.message.is_empty() {
+            builder = builder.failure_reason(root.status.message.clone());
+        }
+    }
+    if let Some(failure_source) = root.string_attribute(attributes::FAILURE_SOURCE) {
+        builder = builder.failure_source(failure_source);
+    }
+
+    for annotation in annotations(root, None) {
+        builder = builder.annotation(annotation);
+    }
+    for child in &children {
+        let durable_step_id = child
+            .string_attribute(attributes::STEP_ID)
+            .unwrap_or_else(|| child.name.clone());
+        for annotation in annotations(child, Some(durable_step_id)) {
+            builder = builder.annotation(annotation);
+        }
+    }
+
+    let steps = children
+        .into_iter()
+        .map(step)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(builder.steps(steps).build())
+}
+
+// Annotation events written by the exporter; other span events are not notes.
+fn annotations(span: &Span, durable_step_id: Option<String>) -> Vec<Annotation> {
+    span.events
+        .iter()
+        .filter(|event| event.name == ANNOTATION_EVENT)
+        .filter_map(|event| {
+            let text = event.attribute(attributes::ANNOTATION_TEXT)?.as_string()?;
+            let created_at = event
+                .attribute(attributes::ANNOTATION_CREATED_AT)
+                .and_then(|value| value.as_string())
+                .and_then(|created_at| DateTime::parse_from_rfc3339(&created_at).ok())
+                .map(|created_at| created_at.with_timezone(&Utc))
+                .or_else(|| timestamp(event.time_unix_nano).ok())?;
+            Some(Annotation::new(durable_step_id.clone(), text, created_at))
+        })
+        .collect()
+}
+
+fn step(span: &Span) -> Result<StepTrace, TraceError> {
+    let scheduled_at = timestamp(span.start_time_unix_nano)?;
+    let completed_at = end_timestamp(span)?;
+    let failed = span.status.code == STATUS_ERROR;
+    // Exported spans are errors when either task failed, so the task's own count decides.
+    let in_task_failures =
+        count(span, attributes::TASK_CONSECUTIVE_FAILURES).unwrap_or(u32::from(failed));
+
+    let mut in_task = TaskInfo::builder(
+        span.string_attribute(attributes::TASK_ID)
+            .unwrap_or_else(|| span.span_id.clone()),
+        span.string_attribute(attributes::TASK_NAME)
+            .unwrap_or_else(|| span.name.clone()),
+        scheduled_at,
+    )
+    .execution_version(count(span, attributes::TASK_EXECUTION_VERSION).unwrap_or(1))
+    .consecutive_failures(in_task_failures);
+    if let Some(completed_at) = completed_at {
+        in_task = if in_task_failures > 0 {
+            in_task.last_failure(completed_at)
+        } else {
+            in_task.last_success(completed_at)
+        };
+    }
+
+    let mut builder = StepTrace::builder(
+        span.string_attribute(attributes::STEP_ID)
+            .unwrap_or_else(|| span.name.clone()),
+        scheduled_at,
+        in_task.build(),
+    );
+    if let Some(completed_at) = completed_at {
+        builder = builder.completed_at(completed_at);
+    }
+    let result = span.string_attribute(attributes::RESULT).or_else(|| {
+        (failed && !span.status.message.is_empty()).then(|| span.status.message.clone())
+    });
+    if let Some(result) = result {
+        builder = builder.result(result);
+    }
+    if let Some(out_task_name) = span.string_attribute(attributes::OUT_TASK_NAME) {
+        let out_task = TaskInfo::builder(
+            span.string_attribute(attributes::OUT_TASK_ID)
+                .unwrap_or_else(|| span.span_id.clone()),
+            out_task_name,
+            completed_at.unwrap_or(scheduled_at),
+        )
+        .execution_version(count(span, attributes::OUT_TASK_EXECUTION_VERSION).unwrap_or(1))
+        .consecutive_failures(
+            count(span, attributes::OUT_TASK_CONSECUTIVE_FAILURES).unwrap_or_default(),
+        );
+        builder = builder.out_task_info(out_task.build());
+    }
+    Ok(builder.build())
+}
+
+// Versions and failure counts; a negative or oversized value is ignored rather than wrapped.
+fn count(span: &Span, key: &str) -> Option<u32> {
+    span.int_attribute(key)
+        .and_then(|value| u32::try_from(value).ok())
+}
+
+// A zero end time means the span has not finished yet.
+fn end_timestamp(span: &Span) -> Result<Option<DateTime<Utc>>, TraceError> {
+    match span.end_time_unix_nano {
+        0 => Ok(None),
+        nanos => timestamp(nanos).map(Some),
+    }
+}
+
+fn timestamp(nanos: u64) -> Result<DateTime<Utc>, TraceError> {
+    i64::try_from(nanos)
+        .map(DateTime::from_timestamp_nanos)
+        .map_err(|_| import_error(FORMAT, &format!("timestamp {} is out of range", nanos)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::otlp;
+    use crate::import::fixtures;
+    use crate::otlp::{attributes, ExportTraceServiceRequest};
+    use crate::{Annotation, DurableTrace, StepTrace, TaskInfo};
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_709_907_600, 0).unwrap() + TimeDelta::seconds(seconds)
+    }
+
+    // Only what OTLP carries: task execution times are the span start and the last success or
+    // failure is the span end.
+    fn trace() -> DurableTrace {
+        let succeeded = StepTrace::builder(
+            "reserve-stock",
+            at(1),
+            TaskInfo::builder("task-1", "reserve", at(1))
+                .execution_version(2)
+                .last_success(at(2))
+                .build(),
+        )
+        .completed_at(at(2))
+        .result("{\"reserved\": 3}")
+        .out_task_info(
+            TaskInfo::builder("task-2", "notify", at(2))
+                .consecutive_failures(1)
+                .build(),
+        )
+        .build();
+        let failed = StepTrace::builder(
+            "charge-card",
+            at(3),
+            TaskInfo::builder("task-3", "charge", at(3))
+                .execution_version(3)
+                .consecutive_failures(2)
+                .last_failure(at(4))
+                .build(),
+        )
+        .completed_at(at(4))
+        .result("card declined")
+        .build();
+        let running = StepTrace::builder(
+            "ship",
+            at(5),
+            TaskInfo::builder("task-4", "ship", at(5)).build(),
+        )
+        .build();
+        DurableTrace::builder("checkout", "order-42", at(0))
+            .status("completed")
+            .completed_at(at(60))
+            .version(7)
+            .payload("{\"order\": 42}")
+            .result("{\"ok\": true}")
+            .annotation(Annotation::new(None, "slow night", at(100)))
+            .annotation(Annotation::new(
+                Some("charge-card".to_string()),
+                "card provider outage",
+                at(200),
+            ))
+            .steps([succeeded, failed, running])
+            .build()
+    }
+
+    #[test]
+    fn export_then_import_round_trips() {
+        let original = trace();
+        let exported = otlp::export_json(&original);
+        let imported = crate::import::import(&exported, None).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+
+    #[test]
+    fn exported_ids_are_stable() {
+        assert_eq!(otlp::export_json(&trace()), otlp::export_json(&trace()));
+    }
+
+    #[test]
+    fn imports_spans_from_other_tools() {
+        let trace = fixtures::import(fixtures::OTLP_COLLECTOR);
+        assert_eq!(trace.name, "checkout");
+        assert_eq!(
+            trace.durable_execution_id,
+            "5b8efff798038103d269b633813fc60c"
+        );
+        assert_eq!(trace.status, "failed");
+        assert_eq!(trace.failure_reason.as_deref(), Some("payment declined"));
+
+        let [reserve, charge] = trace.steps.as_slice() else {
+            panic!("expected two steps, got {:?}", trace.steps);
+        };
+        assert_eq!(reserve.durable_step_id, "reserve-stock");
+        assert_eq!(reserve.in_task_info.consecutive_failures, 0);
+        assert_eq!(reserve.in_task_info.execution_version, 1);
+        // Without the durable attributes an error status counts as one failure.
+        assert_eq!(charge.in_task_info.consecutive_failures, 1);
+        assert_eq!(charge.in_task_info.execution_version, 3);
+        assert_eq!(charge.result.as_deref(), Some("card declined"));
+    }
+
+    #[test]
+    fn out_of_range_counts_fall_back_to_defaults() {
+        let mut request: ExportTraceServiceRequest =
+            serde_json::from_str(&otlp::export_json(&trace())).unwrap();
+        let spans = &mut request.resource_spans[0].scope_spans[0].spans;
+        for span in spans.iter_mut() {
+            for attribute in &mut span.attributes {
+                match attribute.key.as_str() {
+                    attributes::TASK_EXECUTION_VERSION => attribute.value.int_value = Some(-2),
+                    attributes::TASK_CONSECUTIVE_FAILURES => {
+                        attribute.value.int_value = Some(i64::from(u32::MAX) + 1)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let imported =
+            crate::import::import(&serde_json::to_string(&request).unwrap(), None).unwrap();
+        let (reserve, charge) = (&imported.steps[0], &imported.steps[1]);
+        assert_eq!(reserve.in_task_info.execution_version, 1);
+        assert_eq!(charge.in_task_info.execution_version, 1);
+        // The span is an error, so its failure count falls back to one.
+        assert_eq!(charge.in_task_info.consecutive_failures, 1);
+    }
+}
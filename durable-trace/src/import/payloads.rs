@@ -0,0 +1,56 @@
+//! Decoding of Temporal style `{"payloads": [{"metadata": ..., "data": "<base64>"}]}` values.
+
+use serde_json::Value;
+
+/// Turns a payloads value into readable text, falling back to its raw JSON.
+///
+/// A single payload decodes to its data; several decode to a JSON array of their data.
+pub(crate) fn decode(value: &Value) -> String {
+    let Some(payloads) = value.get("payloads").and_then(Value::as_array) else {
+        return value.to_string();
+    };
+    let decoded: Option<Vec<String>> = payloads
+        .iter()
+        .map(|payload| {
+            let data = payload.get("data")?.as_str()?;
+            String::from_utf8(base64_decode(data)?).ok()
+        })
+        .collect();
+    match decoded {
+        Some(decoded) if decoded.len() == 1 => decoded[0].clone(),
+        Some(decoded) => {
+            let values = decoded
+                .iter()
+                .map(|data| serde_json::from_str(data).unwrap_or(Value::String(data.clone())))
+                .collect();
+            Value::Array(values).to_string()
+        }
+        None => value.to_string(),
+    }
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+    {
+        let sextet = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
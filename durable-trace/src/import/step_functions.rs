@@ -0,0 +1,354 @@
+use crate::import::{import_error, text};
+use crate::{DurableTrace, StepTrace, TaskInfo, TraceError};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const FORMAT: &str = "Step Functions";
+
+/// Whether `value` looks like an AWS Step Functions `GetExecutionHistory` response.
+pub fn detect(value: &Value) -> bool {
+    value
+        .get("events")
+        .and_then(Value::as_array)
+        .and_then(|events| events.first())
+        .is_some_and(|event| event.get("type").is_some() && event.get("id").is_some())
+}
+
+/// Folds a Step Functions execution history into a [`DurableTrace`] with one step per state
+/// entered, using the state name as the step id.
+///
+/// Task events are attributed to their state through `previousEventId`, so retries count towards
+/// the state's `consecutive_failures` and `execution_version`. The `error` and `cause` of a failed
+/// execution become its `failure_reason`.
+pub fn import(value: Value) -> Result<DurableTrace, TraceError> {
+    let events = value
+        .get("events")
+        .and_then(Value::as_array)
+        .ok_or_else(|| import_error(FORMAT, "no events found"))?;
+
+    let started = events
+        .iter()
+        .find(|event| event_type(event) == "ExecutionStarted")
+        .ok_or_else(|| import_error(FORMAT, "missing ExecutionStarted event"))?;
+    let started_details = details(started);
+
+    // The history itself does not name the execution; wrapped dumps may carry its ARN.
+    let arn = [
+        value.get("executionArn"),
+        started_details.get("stateMachineAliasArn"),
+        started_details.get("stateMachineVersionArn"),
+        started_details.get("stateMachineArn"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(Value::as_str)
+    .unwrap_or_default();
+    let (name, execution_id) = names_from_arn(arn);
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or(name)
+        .unwrap_or_else(|| "state machine".to_string());
+    let execution_id = execution_id
+        .or_else(|| (!arn.is_empty()).then(|| arn.to_string()))
+        .unwrap_or_else(|| name.clone());
+
+    let mut builder = DurableTrace::builder(name, execution_id, timestamp(started)?);
+    if let Some(input) = started_details.get("input").and_then(Value::as_str) {
+        builder = builder.payload(input);
+    }
+
+    let mut states: Vec<State> = Vec::new();
+    // Event id to the index of the state it belongs to.
+    let mut owners: HashMap<String, usize> = HashMap::new();
+    let mut status = "running";
+
+    for event in events {
+        let kind = event_type(event);
+        let id = text(event.get("id")).unwrap_or_default();
+        let owner = text(event.get("previousEventId")).and_then(|id| owners.get(&id).copied());
+        let event_details = details(event);
+        let at = timestamp(event)?;
+
+        if let Some(state_kind) = kind.strip_suffix("StateEntered") {
+            owners.insert(id.clone(), states.len());
+            states.push(State {
+                id,
+                name: state_name(event_details),
+                kind: state_kind.to_string(),
+                entered_at: at,
+                exited_at: None,
+                resource: None,
+                attempts: 0,
+                failures: 0,
+                first_attempt_at: None,
+                last_success: None,
+                last_failure: None,
+                output: None,
+                error: None,
+            });
+        } else if kind.ends_with("StateExited") {
+            let name = state_name(event_details);
+            let index = owner
+                .filter(|&index| states[index].name == name)
+                .or_else(|| {
+                    states
+                        .iter()
+                        .rposition(|state| state.name == name && state.exited_at.is_none())
+                });
+            if let Some(index) = index {
+                let state = &mut states[index];
+                state.exited_at = Some(at);
+                state.output = event_details
+                    .get("output")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                owners.insert(id, index);
+            }
+        } else if kind.starts_with("Execution") {
+            match kind.as_str() {
+                "ExecutionSucceeded" => {
+                    status = "completed";
+                    builder = builder.completed_at(at);
+                    if let Some(output) = event_details.get("output").and_then(Value::as_str) {
+                        builder = builder.result(output);
+                    }
+                }
+                "ExecutionFailed" | "ExecutionTimedOut" | "ExecutionAborted" => {
+                    status = "failed";
+                    let failure_source = owner
+                        .map(|index| states[index].name.clone())
+                        .unwrap_or_else(|| kind.clone());
+                    builder = builder
+                        .completed_at(at)
+                        .is_error(true)
+                        .failure_reason(error_message(event_details).unwrap_or(kind))
+                        .failure_source(failure_source);
+                    // States do not exit when the execution stops inside them.
+                    for state in states.iter_mut().filter(|state| state.exited_at.is_none()) {
+                        state.exited_at = Some(state.last_failure.unwrap_or(at));
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(index) = owner {
+            owners.insert(id, index);
+            let state = &mut states[index];
+            if kind.ends_with("Scheduled") {
+                state.attempts += 1;
+                state.first_attempt_at.get_or_insert(at);
+                if let Some(resource) = resource(event_details) {
+                    state.resource = Some(resource);
+                }
+            } else if kind.ends_with("Succeeded") {
+                state.last_success = Some(at);
+            } else if kind.ends_with("Failed") || kind.ends_with("TimedOut") {
+                state.failures += 1;
+                state.last_failure = Some(at);
+                state.error = Some(error_message(event_details).unwrap_or(kind));
+            }
+        }
+    }
+
+    Ok(builder
+        .status(status)
+        .steps(states.into_iter().map(State::into_step))
+        .build())
+}
+
+// A state entered during the execution, with the task events attributed to it.
+struct State {
+    id: String,
+    name: String,
+    kind: String,
+    entered_at: DateTime<Utc>,
+    exited_at: Option<DateTime<Utc>>,
+    resource: Option<String>,
+    attempts: u32,
+    failures: u32,
+    first_attempt_at: Option<DateTime<Utc>>,
+    last_success: Option<DateTime<Utc>>,
+    last_failure: Option<DateTime<Utc>>,
+    output: Option<String>,
+    error: Option<String>,
+}
+
+impl State {
+    fn into_step(self) -> StepTrace {
+        let mut task = TaskInfo::builder(
+            self.id,
+            self.resource.unwrap_or(self.kind),
+            self.first_attempt_at.unwrap_or(self.entered_at),
+        )
+        .execution_version(self.attempts.max(1))
+        .consecutive_failures(self.failures);
+        if let Some(last_success) = self.last_success {
+            task = task.last_success(last_success);
+        }
+        if let Some(last_failure) = self.last_failure {
+            task = task.last_failure(last_failure);
+        }
+
+        let mut step = StepTrace::builder(self.name, self.entered_at, task.build());
+        if let Some(exited_at) = self.exited_at {
+            step = step.completed_at(exited_at);
+        }
+        // A state that failed for good has no output, only the error of its last attempt.
+        let result = match (self.output, self.last_success) {
+            (Some(output), _) => Some(output),
+            (None, None) => self.error,
+            (None, Some(_)) => None,
+        };
+        if let Some(result) = result {
+            step = step.result(result);
+        }
+        step.build()
+    }
+}
+
+fn event_type(event: &Value) -> String {
+    event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+// Each event carries its details in a single `<type>EventDetails` field.
+fn details(event: &Value) -> &Value {
+    event
+        .as_object()
+        .and_then(|fields| {
+            fields
+                .iter()
+                .find(|(key, _)| key.ends_with("EventDetails"))
+                .map(|(_, value)| value)
+        })
+        .unwrap_or(&Value::Null)
+}
+
+fn state_name(details: &Value) -> String {
+    details
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("state")
+        .to_string()
+}
+
+// `lambda:invoke` for service integrations, the ARN for activities and legacy Lambda tasks.
+fn resource(details: &Value) -> Option<String> {
+    match (
+        details.get("resourceType").and_then(Value::as_str),
+        details.get("resource").and_then(Value::as_str),
+    ) {
+        (Some(resource_type), Some(resource)) => Some(format!("{}:{}", resource_type, resource)),
+        (None, Some(resource)) => Some(resource.to_string()),
+        _ => None,
+    }
+}
+
+fn error_message(details: &Value) -> Option<String> {
+    let error = details.get("error").and_then(Value::as_str);
+    let cause = details.get("cause").and_then(Value::as_str);
+    match (error, cause) {
+        (Some(error), Some(cause)) => Some(format!("{}: {}", error, cause)),
+        (Some(message), None) | (None, Some(message)) => Some(message.to_string()),
+        (None, None) => None,
+    }
+}
+
+// `arn:aws:states:<region>:<account>:execution:<state machine>:<execution>`; state machine ARNs
+// only yield the name.
+fn names_from_arn(arn: &str) -> (Option<String>, Option<String>) {
+    let parts: Vec<&str> = arn.split(':').collect();
+    match parts.get(5..) {
+        Some(["execution" | "express", state_machine, execution, ..]) => {
+            (Some(state_machine.to_string()), Some(execution.to_string()))
+        }
+        Some(["stateMachine", state_machine, ..]) => (Some(state_machine.to_string()), None),
+        _ => (None, None),
+    }
+}
+
+// The CLI prints ISO 8601 timestamps, the SDKs' JSON serialisers often epoch seconds.
+fn timestamp(event: &Value) -> Result<DateTime<Utc>, TraceError> {
+    let value = event
+        .get("timestamp")
+        .ok_or_else(|| import_error(FORMAT, "event without timestamp"))?;
+    let parsed = match value {
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .map(|at| at.with_timezone(&Utc))
+            .ok(),
+        Value::Number(seconds) => seconds
+            .as_f64()
+            .and_then(|seconds| DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)),
+        _ => None,
+    };
+    parsed.ok_or_else(|| import_error(FORMAT, &format!("invalid timestamp {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::fixtures;
+
+    #[test]
+    fn imports_the_execution() {
+        let trace = fixtures::import(fixtures::STEP_FUNCTIONS_HISTORY);
+        assert_eq!(trace.name, "Checkout");
+        assert_eq!(trace.durable_execution_id, "order-42");
+        assert_eq!(trace.payload.as_deref(), Some("{\"order\":42}"));
+        // The first event carries epoch seconds, the rest ISO 8601.
+        assert_eq!(trace.scheduled_at.to_rfc3339(), "2024-03-08T14:20:01+00:00");
+        let step_ids: Vec<_> = trace
+            .steps
+            .iter()
+            .map(|step| step.durable_step_id.as_str())
+            .collect();
+        assert_eq!(step_ids, ["Charge", "Ship"]);
+    }
+
+    #[test]
+    fn retries_map_to_attempts_and_failures() {
+        let charge = &fixtures::import(fixtures::STEP_FUNCTIONS_HISTORY).steps[0];
+        let task = &charge.in_task_info;
+        assert_eq!(task.task_name, "lambda:invoke");
+        assert_eq!(task.execution_version, 3);
+        // The two failed attempts before the one that succeeded.
+        assert_eq!(task.consecutive_failures, 2);
+        assert_eq!(
+            task.last_failure.unwrap().to_rfc3339(),
+            "2024-03-08T14:20:08+00:00"
+        );
+        assert_eq!(
+            task.last_success.unwrap().to_rfc3339(),
+            "2024-03-08T14:20:11+00:00"
+        );
+        assert_eq!(charge.result.as_deref(), Some("{\"charged\":true}"));
+    }
+
+    #[test]
+    fn failed_execution_names_the_failing_state() {
+        let trace = fixtures::import(fixtures::STEP_FUNCTIONS_HISTORY);
+        assert_eq!(trace.status, "failed");
+        assert_eq!(
+            trace.failure_reason.as_deref(),
+            Some("States.TaskFailed: carrier unavailable")
+        );
+        assert_eq!(trace.failure_source.as_deref(), Some("Ship"));
+
+        let ship = &trace.steps[1];
+        assert_eq!(ship.in_task_info.execution_version, 1);
+        assert_eq!(ship.in_task_info.consecutive_failures, 1);
+        assert_eq!(
+            ship.result.as_deref(),
+            Some("States.TaskFailed: carrier unavailable")
+        );
+        // Closed at its last failure, since the state never exits.
+        assert_eq!(
+            ship.completed_at.unwrap().to_rfc3339(),
+            "2024-03-08T14:20:16+00:00"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::import::{import_error, payloads, text};
+use crate::{DurableTrace, StepTrace, TaskInfo, TraceError};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const FORMAT: &str = "Temporal";
+
+/// Whether `value` looks like a Temporal history export, `{"events": [...]}` optionally nested
+/// under `history`.
+pub fn detect(value: &Value) -> bool {
+    events(value)
+        .and_then(|events| events.first())
+        .is_some_and(|event| event.get("eventType").is_some())
+}
+
+/// Folds a Temporal workflow history into a [`DurableTrace`] with one step per activity attempt.
+///
+/// Temporal records server-side retries only on the `ActivityTaskStarted` event of the final
+/// attempt, so `consecutive_failures` combines its `attempt` with any failures of earlier
+/// activities of the same type scheduled by the workflow itself.
+pub fn import(value: Value) -> Result<DurableTrace, TraceError> {
+    let events = events(&value).ok_or_else(|| import_error(FORMAT, "no events found"))?;
+
+    let started = events
+        .iter()
+        .find(|event| event_type(event) == "workflowexecutionstarted")
+        .ok_or_else(|| import_error(FORMAT, "missing WorkflowExecutionStarted event"))?;
+    let started_attributes = attributes(started);
+    let name = started_attributes
+        .pointer("/workflowType/name")
+        .and_then(Value::as_str)
+        .unwrap_or("workflow")
+        .to_string();
+    let execution_id = [
+        value.pointer("/workflowExecution/workflowId"),
+        value.get("workflowId"),
+        started_attributes.get("workflowId"),
+        started_attributes.get("originalExecutionRunId"),
+        started_attributes.get("firstExecutionRunId"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(Value::as_str)
+    .unwrap_or(&name)
+    .to_string();
+
+    let mut builder = DurableTrace::builder(name, execution_id, event_time(started)?);
+    if let Some(input) = started_attributes.get("input") {
+        builder = builder.payload(payloads::decode(input));
+    }
+
+    let mut activities: HashMap<String, Activity> = HashMap::new();
+    let mut steps: Vec<(String, StepTrace)> = Vec::new();
+    let mut failures_by_type: HashMap<String, u32> = HashMap::new();
+    let mut status = "running";
+
+    for event in events {
+        let attributes = attributes(event);
+        match event_type(event).as_str() {
+            "activitytaskscheduled" => {
+                let activity_type = attributes
+                    .pointer("/activityType/name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("activity")
+                    .to_string();
+                let activity_id = text(attributes.get("activityId")).unwrap_or_default();
+                activities.insert(
+                    text(event.get("eventId")).unwrap_or_default(),
+                    Activity {
+                        step_id: format!("{}/{}", activity_type, activity_id),
+                        activity_type,
+                        activity_id,
+                        scheduled_at: event_time(event)?,
+                        started_at: None,
+                        attempt: 1,
+                    },
+                );
+            }
+            "activitytaskstarted" => {
+                let scheduled_id = text(attributes.get("scheduledEventId")).unwrap_or_default();
+                if let Some(activity) = activities.get_mut(&scheduled_id) {
+                    activity.started_at = Some(event_time(event)?);
+                    activity.attempt = attributes
+                        .get("attempt")
+                        .and_then(number)
+                        .map_or(1, |attempt| {
+                            u32::try_from(attempt.max(1)).unwrap_or(u32::MAX)
+                        });
+                }
+            }
+            "activitytaskcompleted"
+            | "activitytaskfailed"
+            | "activitytasktimedout"
+            | "activitytaskcanceled" => {
+                let scheduled_id = text(attributes.get("scheduledEventId")).unwrap_or_default();
+                let Some(activity) = activities.remove(&scheduled_id) else {
+                    continue;
+                };
+                let completed_at = event_time(event)?;
+                let succeeded = event_type(event) == "activitytaskcompleted";
+                let earlier_failures = failures_by_type
+                    .entry(activity.activity_type.clone())
+                    .or_default();
+                let failures = earlier_failures
+                    .saturating_add(activity.attempt.saturating_sub(1))
+                    .saturating_add(u32::from(!succeeded));
+                *earlier_failures = if succeeded { 0 } else { failures };
+
+                let mut task = TaskInfo::builder(
+                    activity.activity_id,
+                    activity.activity_type,
+                    activity.started_at.unwrap_or(activity.scheduled_at),
+                )
+                .execution_version(activity.attempt)
+                .consecutive_failures(failures);
+                task = if succeeded {
+                    task.last_success(completed_at)
+                } else {
+                    task.last_failure(completed_at)
+                };
+                let result = if succeeded {
+                    attributes.get("result").map(payloads::decode)
+                } else {
+                    Some(failure_message(attributes, &event_type(event)))
+                };
+                let mut step =
+                    StepTrace::builder(activity.step_id, activity.scheduled_at, task.build())
+                        .completed_at(completed_at);
+                if let Some(result) = result {
+                    step = step.result(result);
+                }
+                steps.push((scheduled_id, step.build()));
+            }
+            "workflowexecutioncompleted" => {
+                status = "completed";
+                builder = builder.completed_at(event_time(event)?);
+                if let Some(result) = attributes.get("result") {
+                    builder = builder.result(payloads::decode(result));
+                }
+            }
+            "workflowexecutionfailed"
+            | "workflowexecutiontimedout"
+            | "workflowexecutionterminated"
+            | "workflowexecutioncanceled" => {
+                status = "failed";
+                builder = builder
+                    .completed_at(event_time(event)?)
+                    .is_error(true)
+                    .failure_reason(failure_message(attributes, &event_type(event)))
+                    .failure_source(event_type_name(event));
+            }
+            _ => {}
+        }
+    }
+
+    // Activities still in flight when the history was exported.
+    for (scheduled_id, activity) in activities {
+        let task = TaskInfo::builder(
+            activity.activity_id,
+            activity.activity_type,
+            activity.started_at.unwrap_or(activity.scheduled_at),
+        )
+        .execution_version(activity.attempt)
+        .consecutive_failures(activity.attempt.saturating_sub(1))
+        .build();
+        steps.push((
+            scheduled_id,
+            StepTrace::builder(activity.step_id, activity.scheduled_at, task).build(),
+        ));
+    }
+    steps.sort_by(|(a_id, a), (b_id, b)| {
+        a.scheduled_at
+            .cmp(&b.scheduled_at)
+            .then_with(|| a_id.cmp(b_id))
+    });
+
+    Ok(builder
+        .status(status)
+        .steps(steps.into_iter().map(|(_, step)| step))
+        .build())
+}
+
+struct Activity {
+    step_id: String,
+    activity_type: String,
+    activity_id: String,
+    scheduled_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    attempt: u32,
+}
+
+fn events(value: &Value) -> Option<&Vec<Value>> {
+    value
+        .get("events")
+        .or_else(|| value.pointer("/history/events"))
+        .and_then(Value::as_array)
+}
+
+// Histories use `EVENT_TYPE_ACTIVITY_TASK_SCHEDULED` or `ActivityTaskScheduled` depending on
+// the tool that exported them; both normalise to `activitytaskscheduled`.
+fn event_type(event: &Value) -> String {
+    event
+        .get("eventType")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim_start_matches("EVENT_TYPE_")
+        .replace('_', "")
+        .to_lowercase()
+}
+
+fn event_type_name(event: &Value) -> String {
+    event
+        .get("eventType")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+// Each event carries its details in a single `<eventType>EventAttributes` field.
+fn attributes(event: &Value) -> &Value {
+    event
+        .as_object()
+        .and_then(|fields| {
+            fields
+                .iter()
+                .find(|(key, _)| key.ends_with("EventAttributes"))
+                .map(|(_, value)| value)
+        })
+        .unwrap_or(&Value::Null)
+}
+
+fn failure_message(attributes: &Value, event_type: &str) -> String {
+    attributes
+        .pointer("/failure/message")
+        .or_else(|| attributes.get("reason"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| event_type.to_string())
+}
+
+fn event_time(event: &Value) -> Result<DateTime<Utc>, TraceError> {
+    let time = event
+        .get("eventTime")
+        .and_then(Value::as_str)
+        .ok_or_else(|| import_error(FORMAT, "event without eventTime"))?;
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| import_error(FORMAT, &format!("invalid eventTime {}: {}", time, e)))
+}
+
+// Counters are int64 too, so they may be strings as well.
+fn number(value: &Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::fixtures;
+
+    #[test]
+    fn imports_the_execution() {
+        let trace = fixtures::import(fixtures::TEMPORAL_HISTORY);
+        assert_eq!(trace.name, "OrderWorkflow");
+        assert_eq!(trace.durable_execution_id, "order-42");
+        assert_eq!(trace.status, "completed");
+        assert_eq!(trace.payload.as_deref(), Some("{\"order\":42}"));
+        assert_eq!(
+            trace.completed_at.unwrap().to_rfc3339(),
+            "2024-03-08T14:20:50+00:00"
+        );
+        let step_ids: Vec<_> = trace
+            .steps
+            .iter()
+            .map(|step| step.durable_step_id.as_str())
+            .collect();
+        assert_eq!(step_ids, ["Charge/1", "Ship/2", "Ship/3", "Notify/4"]);
+    }
+
+    #[test]
+    fn server_retries_map_to_attempts_and_failures() {
+        let trace = fixtures::import(fixtures::TEMPORAL_HISTORY);
+        let charge = &trace.steps[0].in_task_info;
+        assert_eq!(charge.execution_version, 3);
+        assert_eq!(charge.consecutive_failures, 2);
+        assert!(charge.last_success.is_some());
+        assert_eq!(
+            charge.execution_time.to_rfc3339(),
+            "2024-03-08T14:20:30+00:00"
+        );
+    }
+
+    #[test]
+    fn workflow_retries_add_up_by_activity_type() {
+        let trace = fixtures::import(fixtures::TEMPORAL_HISTORY);
+        let (failed, retried) = (&trace.steps[1], &trace.steps[2]);
+        assert_eq!(failed.in_task_info.execution_version, 1);
+        assert_eq!(failed.in_task_info.consecutive_failures, 1);
+        assert!(failed.in_task_info.last_failure.is_some());
+        assert_eq!(failed.result.as_deref(), Some("carrier unavailable"));
+
+        // One earlier failed Ship plus one server-side retry of this one.
+        assert_eq!(retried.in_task_info.execution_version, 2);
+        assert_eq!(retried.in_task_info.consecutive_failures, 2);
+        assert!(retried.in_task_info.last_success.is_some());
+    }
+
+    #[test]
+    fn activities_in_flight_are_open_steps() {
+        let notify = &fixtures::import(fixtures::TEMPORAL_HISTORY).steps[3];
+        assert!(notify.completed_at.is_none());
+        assert_eq!(notify.in_task_info.execution_version, 1);
+        assert_eq!(notify.in_task_info.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn oversized_attempts_saturate() {
+        let history = r#"{"events": [
+            {"eventId": "1", "eventTime": "2024-03-08T14:20:00Z", "eventType": "WorkflowExecutionStarted",
+             "workflowExecutionStartedEventAttributes": {"workflowType": {"name": "OrderWorkflow"}}},
+            {"eventId": "2", "eventTime": "2024-03-08T14:20:01Z", "eventType": "ActivityTaskScheduled",
+             "activityTaskScheduledEventAttributes": {"activityId": "1", "activityType": {"name": "Charge"}}},
+            {"eventId": "3", "eventTime": "2024-03-08T14:20:02Z", "eventType": "ActivityTaskStarted",
+             "activityTaskStartedEventAttributes": {"scheduledEventId": "2", "attempt": "4294967296"}},
+            {"eventId": "4", "eventTime": "2024-03-08T14:20:03Z", "eventType": "ActivityTaskFailed",
+             "activityTaskFailedEventAttributes": {"scheduledEventId": "2"}}
+        ]}"#;
+        let trace = crate::import::import(history, None).unwrap();
+        let charge = &trace.steps[0].in_task_info;
+        assert_eq!(charge.execution_version, u32::MAX);
+        assert_eq!(charge.consecutive_failures, u32::MAX);
+    }
+}
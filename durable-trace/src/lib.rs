@@ -0,0 +1,19 @@
+//! Durable execution trace model shared by the web viewer and native tooling.
+//!
+//! Everything here compiles on native targets; browser concerns live in the viewer crate.
+
+pub mod analysis;
+pub mod export;
+pub mod import;
+pub mod migration;
+mod model;
+pub mod otlp;
+pub mod schema;
+pub mod validation;
+
+pub use chrono;
+pub use migration::{parse_trace, TraceError, CURRENT_SCHEMA_VERSION};
+pub use model::{
+    Annotation, DurableTrace, DurableTraceBuilder, Marker, StepTrace, StepTraceBuilder, TaskInfo,
+    TaskInfoBuilder,
+};
@@ -0,0 +1,348 @@
+use crate::schema::{self, SchemaViolation};
+use crate::DurableTrace;
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Schema version written by this build. Bump it together with a new entry in [`MIGRATIONS`].
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+// Timestamp fields, already camelCased, that older layouts stored as epoch millis.
+const TIMESTAMP_FIELDS: [&str; 5] = [
+    "scheduledAt",
+    "completedAt",
+    "executionTime",
+    "lastFailure",
+    "lastSuccess",
+];
+
+type Migration = fn(Value) -> Result<Value, TraceError>;
+
+// Each entry upgrades a document from `version` to `version + 1`.
+const MIGRATIONS: [(u32, Migration); 1] = [(1, migrate_v1_to_v2)];
+
+#[derive(Debug)]
+pub enum TraceError {
+    Json(serde_json::Error),
+    InvalidVersion(String),
+    UnsupportedVersion(u32),
+    Migration {
+        from: u32,
+        reason: String,
+    },
+    Schema(Vec<SchemaViolation>),
+    Import {
+        format: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::Json(e) => write!(f, "{}", e),
+            TraceError::InvalidVersion(version) => {
+                write!(f, "Invalid schema version {}", version)
+            }
+            TraceError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported schema version {} (this build understands up to {})",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+            TraceError::Migration { from, reason } => {
+                write!(
+                    f,
+                    "Failed to migrate from schema version {}: {}",
+                    from, reason
+                )
+            }
+            TraceError::Schema(violations) => {
+                write!(f, "Trace does not match the schema:")?;
+                for violation in violations {
+                    write!(f, "\n{}", violation)?;
+                }
+                Ok(())
+            }
+            TraceError::Import { format, reason } => {
+                write!(f, "Failed to import {} trace: {}", format, reason)
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for TraceError {
+    fn from(e: serde_json::Error) -> Self {
+        TraceError::Json(e)
+    }
+}
+
+/// Parses a trace in any known layout, upgrading it to the current schema first.
+///
+/// The upgraded document is checked against the published JSON Schema so producers get the
+/// same errors here as from any other validator.
+pub fn parse_trace(json: &str) -> Result<DurableTrace, TraceError> {
+    parse_value(serde_json::from_str::<Value>(json)?)
+}
+
+/// Like [`parse_trace`], for a document that has already been read into a [`Value`].
+pub fn parse_value(value: Value) -> Result<DurableTrace, TraceError> {
+    let value = migrate(value)?;
+    let violations = schema::validate(&value);
+    if !violations.is_empty() {
+        return Err(TraceError::Schema(violations));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Runs every migration needed to bring `value` to [`CURRENT_SCHEMA_VERSION`].
+pub fn migrate(mut value: Value) -> Result<Value, TraceError> {
+    let mut version = detect_version(&value)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(TraceError::UnsupportedVersion(version));
+    }
+    while version < CURRENT_SCHEMA_VERSION {
+        let (_, migration) = MIGRATIONS
+            .iter()
+            .find(|(from, _)| *from == version)
+            .ok_or(TraceError::UnsupportedVersion(version))?;
+        value = migration(value)?;
+        version += 1;
+    }
+    if let Value::Object(map) = &mut value {
+        map.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version));
+    }
+    Ok(value)
+}
+
+// Traces written before the version field existed mix the v1 variations freely, so they all
+// go through the v1 migration, which leaves anything already in the current layout alone.
+fn detect_version(value: &Value) -> Result<u32, TraceError> {
+    let Some(explicit) = value
+        .get(SCHEMA_VERSION_KEY)
+        .or_else(|| value.get("schema_version"))
+    else {
+        return Ok(1);
+    };
+    explicit
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| TraceError::InvalidVersion(explicit.to_string()))
+}
+
+// v1: snake_case keys, epoch-millis timestamps and steps nested under `history`, in any mix.
+// Idempotent, since unversioned current traces come through here too.
+fn migrate_v1_to_v2(value: Value) -> Result<Value, TraceError> {
+    let mut value = camel_case_keys(value);
+    let Value::Object(map) = &mut value else {
+        return Err(TraceError::Migration {
+            from: 1,
+            reason: "trace is not a json object".to_string(),
+        });
+    };
+    if let Some(history) = map.remove("history") {
+        let steps = match history {
+            Value::Object(mut history) => history.remove("steps").unwrap_or_default(),
+            steps @ Value::Array(_) => steps,
+            _ => Value::Null,
+        };
+        if !steps.is_null() {
+            map.insert("steps".to_string(), steps);
+        }
+    }
+    convert_timestamps(map)?;
+    if let Some(Value::Array(steps)) = map.get_mut("steps") {
+        for step in steps.iter_mut().filter_map(Value::as_object_mut) {
+            convert_timestamps(step)?;
+            for info in ["inTaskInfo", "outTaskInfo"] {
+                if let Some(Value::Object(info)) = step.get_mut(info) {
+                    convert_timestamps(info)?;
+                }
+            }
+        }
+    }
+    Ok(value)
+}
+
+fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (to_camel_case(&key), camel_case_keys(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(camel_case_keys).collect()),
+        other => other,
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper_next = !result.is_empty();
+        } else if upper_next {
+            result.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn convert_timestamps(map: &mut Map<String, Value>) -> Result<(), TraceError> {
+    for field in TIMESTAMP_FIELDS {
+        if let Some(value) = map.get_mut(field) {
+            if let Some(millis) = value.as_i64() {
+                let date = DateTime::from_timestamp_millis(millis).ok_or_else(|| {
+                    TraceError::Migration {
+                        from: 1,
+                        reason: format!("{} is out of range: {}", field, millis),
+                    }
+                })?;
+                *value = Value::String(date.to_rfc3339());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 2024-03-08T14:20:00Z and an hour later, as epoch millis.
+    const SCHEDULED_MILLIS: i64 = 1_709_907_600_000;
+    const COMPLETED_MILLIS: i64 = 1_709_911_200_000;
+
+    fn current() -> Value {
+        json!({
+            "name": "order",
+            "durableExecutionId": "exec-1",
+            "scheduledAt": "2024-03-08T14:20:00Z",
+            "completedAt": "2024-03-08T15:20:00Z",
+            "status": "completed",
+            "version": 1,
+            "steps": [{
+                "durableStepId": "step-1",
+                "scheduledAt": "2024-03-08T14:20:00Z",
+                "completedAt": "2024-03-08T15:20:00Z",
+                "inTaskInfo": {
+                    "id": "task-1",
+                    "taskName": "charge_card",
+                    "executionTime": "2024-03-08T14:20:00Z",
+                    "consecutiveFailures": 0,
+                    "executionVersion": 1
+                }
+            }]
+        })
+    }
+
+    fn assert_current(value: Value) {
+        let trace = parse_value(value).expect("trace parses");
+        assert_eq!(trace.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(trace.durable_execution_id, "exec-1");
+        assert_eq!(trace.scheduled_at.to_rfc3339(), "2024-03-08T14:20:00+00:00");
+        assert_eq!(
+            trace.completed_at.unwrap().to_rfc3339(),
+            "2024-03-08T15:20:00+00:00"
+        );
+        assert_eq!(trace.steps.len(), 1);
+        assert_eq!(trace.steps[0].in_task_info.task_name, "charge_card");
+    }
+
+    #[test]
+    fn current_trace_with_version() {
+        let mut value = current();
+        value["schemaVersion"] = json!(CURRENT_SCHEMA_VERSION);
+        assert_current(value);
+    }
+
+    #[test]
+    fn current_trace_without_version() {
+        assert_current(current());
+    }
+
+    #[test]
+    fn snake_case_keys() {
+        let value = json!({
+            "name": "order",
+            "durable_execution_id": "exec-1",
+            "scheduled_at": "2024-03-08T14:20:00Z",
+            "completed_at": "2024-03-08T15:20:00Z",
+            "status": "completed",
+            "version": 1,
+            "steps": [{
+                "durable_step_id": "step-1",
+                "scheduled_at": "2024-03-08T14:20:00Z",
+                "completed_at": "2024-03-08T15:20:00Z",
+                "in_task_info": {
+                    "id": "task-1",
+                    "task_name": "charge_card",
+                    "execution_time": "2024-03-08T14:20:00Z",
+                    "consecutive_failures": 0,
+                    "execution_version": 1
+                }
+            }]
+        });
+        assert_current(value);
+    }
+
+    #[test]
+    fn camel_case_epoch_millis() {
+        let mut value = current();
+        value["scheduledAt"] = json!(SCHEDULED_MILLIS);
+        value["completedAt"] = json!(COMPLETED_MILLIS);
+        value["steps"][0]["scheduledAt"] = json!(SCHEDULED_MILLIS);
+        value["steps"][0]["completedAt"] = json!(COMPLETED_MILLIS);
+        value["steps"][0]["inTaskInfo"]["executionTime"] = json!(SCHEDULED_MILLIS);
+        assert_current(value);
+    }
+
+    #[test]
+    fn steps_nested_under_history() {
+        let mut value = current();
+        let steps = value.as_object_mut().unwrap().remove("steps").unwrap();
+        value["history"] = json!({ "steps": steps.clone() });
+        assert_current(value.clone());
+
+        value["history"] = steps;
+        assert_current(value);
+    }
+
+    #[test]
+    fn explicit_v1_is_migrated() {
+        let mut value = current();
+        value["schemaVersion"] = json!(1);
+        value["scheduledAt"] = json!(SCHEDULED_MILLIS);
+        assert_current(value);
+    }
+
+    #[test]
+    fn future_version_is_unsupported() {
+        let mut value = current();
+        value["schemaVersion"] = json!(CURRENT_SCHEMA_VERSION + 1);
+        assert!(matches!(
+            migrate(value),
+            Err(TraceError::UnsupportedVersion(version)) if version == CURRENT_SCHEMA_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn oversized_version_is_not_truncated() {
+        let mut value = current();
+        value["schemaVersion"] = json!((1_u64 << 32) + 2);
+        assert!(matches!(migrate(value), Err(TraceError::InvalidVersion(_))));
+    }
+
+    #[test]
+    fn non_numeric_version_is_invalid() {
+        let mut value = current();
+        value["schemaVersion"] = json!("2");
+        assert!(matches!(migrate(value), Err(TraceError::InvalidVersion(_))));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::migration::CURRENT_SCHEMA_VERSION;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: String,
+    pub task_name: String,
+    pub execution_time: DateTime<Utc>,
+    /// Failed attempts in a row leading up to the latest one, counting the latest if it failed
+    /// too. A task that succeeded on its third attempt has 2; the next task starts from 0.
+    pub consecutive_failures: u32,
+    pub execution_version: u32,
+    pub last_failure: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+impl TaskInfo {
+    pub fn builder(
+        id: impl Into<String>,
+        task_name: impl Into<String>,
+        execution_time: DateTime<Utc>,
+    ) -> TaskInfoBuilder {
+        TaskInfoBuilder(TaskInfo {
+            id: id.into(),
+            task_name: task_name.into(),
+            execution_time,
+            consecutive_failures: 0,
+            execution_version: 1,
+            last_failure: None,
+            last_success: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskInfoBuilder(TaskInfo);
+
+impl TaskInfoBuilder {
+    pub fn consecutive_failures(mut self, consecutive_failures: u32) -> Self {
+        self.0.consecutive_failures = consecutive_failures;
+        self
+    }
+
+    pub fn execution_version(mut self, execution_version: u32) -> Self {
+        self.0.execution_version = execution_version;
+        self
+    }
+
+    pub fn last_failure(mut self, last_failure: DateTime<Utc>) -> Self {
+        self.0.last_failure = Some(last_failure);
+        self
+    }
+
+    pub fn last_success(mut self, last_success: DateTime<Utc>) -> Self {
+        self.0.last_success = Some(last_success);
+        self
+    }
+
+    pub fn build(self) -> TaskInfo {
+        self.0
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepTrace {
+    pub durable_step_id: String,
+    pub result: Option<String>,
+    pub scheduled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub in_task_info: TaskInfo,
+    pub out_task_info: Option<TaskInfo>,
+}
+
+impl StepTrace {
+    pub fn builder(
+        durable_step_id: impl Into<String>,
+        scheduled_at: DateTime<Utc>,
+        in_task_info: TaskInfo,
+    ) -> StepTraceBuilder {
+        StepTraceBuilder(StepTrace {
+            durable_step_id: durable_step_id.into(),
+            result: None,
+            scheduled_at,
+            completed_at: None,
+            in_task_info,
+            out_task_info: None,
+        })
+    }
+
+    pub fn in_task_text(&self) -> String {
+        serde_json::to_string_pretty(&self.in_task_info).unwrap_or_default()
+    }
+
+    pub fn out_task_text(&self) -> String {
+        self.out_task_info
+            .as_ref()
+            .map(|info| serde_json::to_string_pretty(&info).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    pub fn completed_at_text(&self) -> String {
+        self.completed_at
+            .as_ref()
+            .map(|completed_at| completed_at.to_rfc3339())
+            .unwrap_or("-".to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StepTraceBuilder(StepTrace);
+
+impl StepTraceBuilder {
+    pub fn result(mut self, result: impl Into<String>) -> Self {
+        self.0.result = Some(result.into());
+        self
+    }
+
+    pub fn completed_at(mut self, completed_at: DateTime<Utc>) -> Self {
+        self.0.completed_at = Some(completed_at);
+        self
+    }
+
+    pub fn out_task_info(mut self, out_task_info: TaskInfo) -> Self {
+        self.0.out_task_info = Some(out_task_info);
+        self
+    }
+
+    pub fn build(self) -> StepTrace {
+        self.0
+    }
+}
+
+/// A note left on a trace during review, on the whole execution or on the steps with
+/// `durable_step_id`.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub durable_step_id: Option<String>,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Annotation {
+    pub fn new(
+        durable_step_id: Option<String>,
+        text: impl Into<String>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Annotation {
+            durable_step_id,
+            text: text.into(),
+            created_at,
+        }
+    }
+
+    /// Whether this annotation is about `step` rather than the whole execution.
+    pub fn is_on_step(&self, step: &StepTrace) -> bool {
+        self.durable_step_id.as_deref() == Some(step.durable_step_id.as_str())
+    }
+}
+
+/// An external event such as a deploy, an alert or an outage, at a point in time or, with an
+/// `end`, over a range.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Marker {
+    pub name: String,
+    pub start: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+    /// Free-form category such as `deploy`, `alert` or `outage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+impl Marker {
+    pub fn point(name: impl Into<String>, at: DateTime<Utc>) -> Self {
+        Marker {
+            name: name.into(),
+            start: at,
+            end: None,
+            kind: None,
+        }
+    }
+
+    pub fn range(name: impl Into<String>, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Marker {
+            end: Some(end),
+            ..Marker::point(name, start)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DurableTrace {
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
+    pub name: String,
+    pub durable_execution_id: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub payload: Option<String>,
+    pub result: Option<String>,
+    pub is_error: Option<bool>,
+    pub status: String,
+    pub failure_reason: Option<String>,
+    pub failure_source: Option<String>,
+    pub version: u32,
+    pub steps: Vec<StepTrace>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<Marker>,
+}
+
+fn current_schema_version() -> u32 {
+    CURRENT_SCHEMA_VERSION
+}
+
+impl DurableTrace {
+    /// Starts a `running` trace with no steps.
+    pub fn builder(
+        name: impl Into<String>,
+        durable_execution_id: impl Into<String>,
+        scheduled_at: DateTime<Utc>,
+    ) -> DurableTraceBuilder {
+        DurableTraceBuilder(DurableTrace {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            name: name.into(),
+            durable_execution_id: durable_execution_id.into(),
+            scheduled_at,
+            completed_at: None,
+            payload: None,
+            result: None,
+            is_error: None,
+            status: "running".to_string(),
+            failure_reason: None,
+            failure_source: None,
+            version: 1,
+            steps: Vec::new(),
+            annotations: Vec::new(),
+            markers: Vec::new(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DurableTraceBuilder(DurableTrace);
+
+impl DurableTraceBuilder {
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.0.status = status.into();
+        self
+    }
+
+    pub fn completed_at(mut self, completed_at: DateTime<Utc>) -> Self {
+        self.0.completed_at = Some(completed_at);
+        self
+    }
+
+    pub fn payload(mut self, payload: impl Into<String>) -> Self {
+        self.0.payload = Some(payload.into());
+        self
+    }
+
+    pub fn result(mut self, result: impl Into<String>) -> Self {
+        self.0.result = Some(result.into());
+        self
+    }
+
+    pub fn is_error(mut self, is_error: bool) -> Self {
+        self.0.is_error = Some(is_error);
+        self
+    }
+
+    pub fn failure_reason(mut self, failure_reason: impl Into<String>) -> Self {
+        self.0.failure_reason = Some(failure_reason.into());
+        self
+    }
+
+    pub fn failure_source(mut self, failure_source: impl Into<String>) -> Self {
+        self.0.failure_source = Some(failure_source.into());
+        self
+    }
+
+    pub fn version(mut self, version: u32) -> Self {
+        self.0.version = version;
+        self
+    }
+
+    pub fn step(mut self, step: StepTrace) -> Self {
+        self.0.steps.push(step);
+        self
+    }
+
+    pub fn steps(mut self, steps: impl IntoIterator<Item = StepTrace>) -> Self {
+        self.0.steps.extend(steps);
+        self
+    }
+
+    pub fn annotation(mut self, annotation: Annotation) -> Self {
+        self.0.annotations.push(annotation);
+        self
+    }
+
+    pub fn marker(mut self, marker: Marker) -> Self {
+        self.0.markers.push(marker);
+        self
+    }
+
+    pub fn build(self) -> DurableTrace {
+        self.0
+    }
+}
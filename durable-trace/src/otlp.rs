@@ -0,0 +1,265 @@
+//! Serde model of the OTLP/JSON trace encoding, shared by the importer and exporter.
+//!
+//! Only the parts of `ExportTraceServiceRequest` that map onto a durable trace are modelled.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Span attribute keys used to round-trip durable trace fields through OpenTelemetry.
+pub mod attributes {
+    pub const EXECUTION_ID: &str = "durable.execution_id";
+    pub const EXECUTION_VERSION: &str = "durable.version";
+    pub const PAYLOAD: &str = "durable.payload";
+    pub const RESULT: &str = "durable.result";
+    pub const FAILURE_SOURCE: &str = "durable.failure_source";
+    pub const STEP_ID: &str = "durable.step_id";
+    pub const TASK_ID: &str = "durable.task.id";
+    pub const TASK_NAME: &str = "durable.task.name";
+    pub const TASK_CONSECUTIVE_FAILURES: &str = "durable.task.consecutive_failures";
+    pub const TASK_EXECUTION_VERSION: &str = "durable.task.execution_version";
+    pub const OUT_TASK_ID: &str = "durable.out_task.id";
+    pub const OUT_TASK_NAME: &str = "durable.out_task.name";
+    pub const OUT_TASK_CONSECUTIVE_FAILURES: &str = "durable.out_task.consecutive_failures";
+    pub const OUT_TASK_EXECUTION_VERSION: &str = "durable.out_task.execution_version";
+    pub const ANNOTATION_TEXT: &str = "durable.annotation.text";
+    pub const ANNOTATION_CREATED_AT: &str = "durable.annotation.created_at";
+}
+
+/// Name of the span events that carry [`crate::Annotation`]s.
+pub const ANNOTATION_EVENT: &str = "durable.annotation";
+
+pub const STATUS_UNSET: i32 = 0;
+pub const STATUS_OK: i32 = 1;
+pub const STATUS_ERROR: i32 = 2;
+
+pub const SPAN_KIND_INTERNAL: i32 = 1;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTraceServiceRequest {
+    #[serde(default)]
+    pub resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSpans {
+    #[serde(default)]
+    pub resource: Resource,
+    // Collectors older than OTLP 0.15 still write `instrumentationLibrarySpans`.
+    #[serde(default, alias = "instrumentationLibrarySpans")]
+    pub scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    #[serde(default)]
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSpans {
+    #[serde(default, alias = "instrumentationLibrary")]
+    pub scope: Scope,
+    #[serde(default)]
+    pub spans: Vec<Span>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Scope {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub kind: i32,
+    #[serde(with = "nanos")]
+    pub start_time_unix_nano: u64,
+    #[serde(default, with = "nanos")]
+    pub end_time_unix_nano: u64,
+    #[serde(default)]
+    pub attributes: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub status: Status,
+}
+
+impl Span {
+    pub fn attribute(&self, key: &str) -> Option<&AnyValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| &attribute.value)
+    }
+
+    pub fn string_attribute(&self, key: &str) -> Option<String> {
+        self.attribute(key).and_then(AnyValue::as_string)
+    }
+
+    pub fn int_attribute(&self, key: &str) -> Option<i64> {
+        self.attribute(key).and_then(AnyValue::as_i64)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    #[serde(with = "nanos")]
+    pub time_unix_nano: u64,
+    pub name: String,
+    #[serde(default)]
+    pub attributes: Vec<KeyValue>,
+}
+
+impl Event {
+    pub fn attribute(&self, key: &str) -> Option<&AnyValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| &attribute.value)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+impl KeyValue {
+    pub fn string(key: &str, value: impl Into<String>) -> Self {
+        KeyValue {
+            key: key.to_string(),
+            value: AnyValue {
+                string_value: Some(value.into()),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn int(key: &str, value: i64) -> Self {
+        KeyValue {
+            key: key.to_string(),
+            value: AnyValue {
+                int_value: Some(value),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn bool(key: &str, value: bool) -> Self {
+        KeyValue {
+            key: key.to_string(),
+            value: AnyValue {
+                bool_value: Some(value),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AnyValue {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "int64")]
+    pub int_value: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub double_value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bool_value: Option<bool>,
+}
+
+impl AnyValue {
+    pub fn as_string(&self) -> Option<String> {
+        self.string_value
+            .clone()
+            .or_else(|| self.int_value.map(|value| value.to_string()))
+            .or_else(|| self.double_value.map(|value| value.to_string()))
+            .or_else(|| self.bool_value.map(|value| value.to_string()))
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.int_value
+            .or_else(|| self.double_value.map(|value| value as i64))
+            .or_else(|| self.string_value.as_ref()?.parse().ok())
+    }
+}
+
+// OTLP/JSON encodes 64 bit integers as strings, but plenty of producers write plain numbers.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+impl StringOrNumber {
+    fn parse<T: std::str::FromStr>(&self) -> Option<T> {
+        match self {
+            StringOrNumber::String(text) => text.parse().ok(),
+            StringOrNumber::Number(number) => number.to_string().parse().ok(),
+        }
+    }
+}
+
+mod nanos {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        StringOrNumber::deserialize(deserializer)?
+            .parse()
+            .ok_or_else(|| serde::de::Error::custom("invalid unix nano timestamp"))
+    }
+}
+
+mod int64 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        Option::<StringOrNumber>::deserialize(deserializer)?
+            .map(|value| {
+                value
+                    .parse()
+                    .ok_or_else(|| serde::de::Error::custom("invalid int64 value"))
+            })
+            .transpose()
+    }
+}
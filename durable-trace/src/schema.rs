@@ -0,0 +1,383 @@
+use crate::DurableTrace;
+use chrono::DateTime;
+use schemars::schema_for;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// File name the schema is offered under for download.
+pub const SCHEMA_FILE_NAME: &str = "durable-trace.schema.json";
+
+/// A single place where a document does not match the trace schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer into the validated document, e.g. `/steps/1/scheduledAt`.
+    pub instance_path: String,
+    /// JSON pointer into the schema keyword that failed, e.g. `#/definitions/StepTrace/required`.
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let instance_path = if self.instance_path.is_empty() {
+            "/"
+        } else {
+            &self.instance_path
+        };
+        write!(
+            f,
+            "{}: {} ({})",
+            instance_path, self.message, self.schema_path
+        )
+    }
+}
+
+/// JSON Schema for [`DurableTrace`], generated from the Rust types.
+pub fn trace_schema() -> Value {
+    serde_json::to_value(schema_for!(DurableTrace)).unwrap_or_default()
+}
+
+pub fn trace_schema_text() -> String {
+    serde_json::to_string_pretty(&trace_schema()).unwrap_or_default()
+}
+
+/// Validates `instance` against [`trace_schema`], returning every violation found.
+///
+/// Only the keywords emitted by `schemars` for the trace types are understood: `$ref`, `type`,
+/// `anyOf`, `allOf`, `format` (`date-time` only), `minimum`, `required`, `properties` and `items`.
+/// Anything else is not checked, notably `maximum` and the upper bound of `uint32`, and
+/// `additionalProperties`, so unknown fields pass and oversized counts fail later in serde.
+pub fn validate(instance: &Value) -> Vec<SchemaViolation> {
+    let schema = trace_schema();
+    let mut validator = Validator {
+        root: &schema,
+        violations: Vec::new(),
+    };
+    validator.check(&schema, instance, "#", "");
+    validator.violations
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    violations: Vec<SchemaViolation>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&mut self, schema: &'a Value, instance: &Value, schema_path: &str, path: &str) {
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => self.check(target, instance, reference, path),
+                None => self.report(path, schema_path, "$ref", "unresolvable reference"),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            if !matches_type(types, instance) {
+                let message = format!("expected {}, found {}", type_names(types), kind(instance));
+                self.report(path, schema_path, "type", &message);
+                // Nothing below makes sense for a value of the wrong type.
+                return;
+            }
+        }
+
+        if let Some(Value::Array(subschemas)) = schema.get("anyOf") {
+            let matched = subschemas
+                .iter()
+                .any(|subschema| self.nested(subschema, instance).is_empty());
+            if !matched {
+                // Report the closest branch so the message points at the actual problem.
+                let closest = subschemas
+                    .iter()
+                    .enumerate()
+                    .map(|(i, subschema)| (i, self.nested(subschema, instance)))
+                    .filter(|(_, violations)| !violations.is_empty())
+                    .min_by_key(|(_, violations)| violations.len());
+                if let Some((i, _)) = closest {
+                    let branch_path = format!("{}/anyOf/{}", schema_path, i);
+                    self.check(&subschemas[i], instance, &branch_path, path);
+                }
+            }
+        }
+
+        if let Some(Value::Array(subschemas)) = schema.get("allOf") {
+            for (i, subschema) in subschemas.iter().enumerate() {
+                self.check(
+                    subschema,
+                    instance,
+                    &format!("{}/allOf/{}", schema_path, i),
+                    path,
+                );
+            }
+        }
+
+        if let Some(format) = schema.get("format").and_then(Value::as_str) {
+            if let Some(text) = instance.as_str() {
+                if format == "date-time" && DateTime::parse_from_rfc3339(text).is_err() {
+                    self.report(
+                        path,
+                        schema_path,
+                        "format",
+                        &format!("\"{}\" is not an RFC 3339 date-time", text),
+                    );
+                }
+            }
+        }
+
+        if let (Some(minimum), Some(number)) = (
+            schema.get("minimum").and_then(Value::as_f64),
+            instance.as_f64(),
+        ) {
+            if number < minimum {
+                let message = format!("{} is less than the minimum of {}", number, minimum);
+                self.report(path, schema_path, "minimum", &message);
+            }
+        }
+
+        if let Value::Object(fields) = instance {
+            self.check_object(schema, fields, schema_path, path);
+        }
+
+        if let (Some(items), Value::Array(elements)) = (schema.get("items"), instance) {
+            let items_path = format!("{}/items", schema_path);
+            for (i, element) in elements.iter().enumerate() {
+                self.check(items, element, &items_path, &format!("{}/{}", path, i));
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &'a Map<String, Value>,
+        fields: &Map<String, Value>,
+        schema_path: &str,
+        path: &str,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    let message = format!("missing required property \"{}\"", name);
+                    self.report(path, schema_path, "required", &message);
+                }
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                if let Some(value) = fields.get(name) {
+                    let property_path = format!("{}/properties/{}", schema_path, name);
+                    self.check(
+                        property,
+                        value,
+                        &property_path,
+                        &format!("{}/{}", path, name),
+                    );
+                }
+            }
+        }
+    }
+
+    fn nested(&self, schema: &'a Value, instance: &Value) -> Vec<SchemaViolation> {
+        let mut validator = Validator {
+            root: self.root,
+            violations: Vec::new(),
+        };
+        validator.check(schema, instance, "#", "");
+        validator.violations
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        self.root.pointer(reference.strip_prefix('#')?)
+    }
+
+    fn report(&mut self, path: &str, schema_path: &str, keyword: &str, message: &str) {
+        self.violations.push(SchemaViolation {
+            instance_path: path.to_string(),
+            schema_path: format!("{}/{}", schema_path, keyword),
+            message: message.to_string(),
+        });
+    }
+}
+
+fn matches_type(types: &Value, instance: &Value) -> bool {
+    match types {
+        Value::String(name) => matches_type_name(name, instance),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| matches_type_name(name, instance)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(name: &str, instance: &Value) -> bool {
+    match name {
+        "integer" => instance.is_i64() || instance.is_u64(),
+        "number" => instance.is_number(),
+        other => kind(instance) == other,
+    }
+}
+
+fn type_names(types: &Value) -> String {
+    match types {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+fn kind(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trace() -> Value {
+        json!({
+            "name": "order",
+            "durableExecutionId": "exec-1",
+            "scheduledAt": "2024-03-08T14:20:00Z",
+            "status": "running",
+            "version": 1,
+            "steps": [{
+                "durableStepId": "step-1",
+                "scheduledAt": "2024-03-08T14:20:00Z",
+                "inTaskInfo": {
+                    "id": "task-1",
+                    "taskName": "charge_card",
+                    "executionTime": "2024-03-08T14:20:00Z",
+                    "consecutiveFailures": 0,
+                    "executionVersion": 1
+                }
+            }]
+        })
+    }
+
+    fn single(instance: &Value) -> SchemaViolation {
+        let mut violations = validate(instance);
+        assert_eq!(violations.len(), 1, "{:?}", violations);
+        violations.remove(0)
+    }
+
+    #[test]
+    fn valid_trace_passes() {
+        assert_eq!(validate(&trace()), []);
+    }
+
+    #[test]
+    fn missing_required_field() {
+        let mut instance = trace();
+        instance.as_object_mut().unwrap().remove("status");
+        let violation = single(&instance);
+        assert_eq!(violation.instance_path, "");
+        assert_eq!(violation.schema_path, "#/required");
+        assert_eq!(violation.message, "missing required property \"status\"");
+        assert_eq!(
+            violation.to_string(),
+            "/: missing required property \"status\" (#/required)"
+        );
+    }
+
+    #[test]
+    fn wrong_type() {
+        let mut instance = trace();
+        instance["version"] = json!("1");
+        let violation = single(&instance);
+        assert_eq!(violation.instance_path, "/version");
+        assert_eq!(violation.schema_path, "#/properties/version/type");
+        assert_eq!(violation.message, "expected integer, found string");
+    }
+
+    #[test]
+    fn bad_date_time() {
+        let mut instance = trace();
+        instance["scheduledAt"] = json!("yesterday");
+        let violation = single(&instance);
+        assert_eq!(violation.schema_path, "#/properties/scheduledAt/format");
+        assert!(violation.message.contains("RFC 3339"), "{}", violation);
+    }
+
+    #[test]
+    fn below_minimum() {
+        let mut instance = trace();
+        instance["version"] = json!(-1);
+        let violation = single(&instance);
+        assert_eq!(violation.schema_path, "#/properties/version/minimum");
+    }
+
+    #[test]
+    fn nested_step_paths() {
+        let mut instance = trace();
+        instance["steps"][0]["scheduledAt"] = json!(42);
+        instance["steps"][0]["inTaskInfo"]
+            .as_object_mut()
+            .unwrap()
+            .remove("taskName");
+        let violations = validate(&instance);
+        let paths: Vec<_> = violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.instance_path.as_str(),
+                    violation.schema_path.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("/steps/0/inTaskInfo", "#/definitions/TaskInfo/required"),
+                (
+                    "/steps/0/scheduledAt",
+                    "#/definitions/StepTrace/properties/scheduledAt/type"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn any_of_reports_the_closest_branch() {
+        let schema = json!({
+            "anyOf": [
+                {"type": "object", "required": ["a", "b", "c"]},
+                {"type": "object", "required": ["a"]},
+            ]
+        });
+        let mut validator = Validator {
+            root: &schema,
+            violations: Vec::new(),
+        };
+        validator.check(&schema, &json!({"b": 1}), "#", "");
+        assert_eq!(
+            validator.violations,
+            [SchemaViolation {
+                instance_path: String::new(),
+                schema_path: "#/anyOf/1/required".to_string(),
+                message: "missing required property \"a\"".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unchecked_keywords_pass() {
+        let mut instance = trace();
+        instance["unknown"] = json!(true);
+        instance["version"] = json!(u64::MAX);
+        assert_eq!(validate(&instance), []);
+    }
+}
@@ -0,0 +1,303 @@
+use crate::DurableTrace;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+const KNOWN_STATUSES: [&str; 3] = ["running", "failed", "completed"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Something in a trace that is well formed but does not add up.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    pub severity: Severity,
+    /// The step the finding refers to, or `None` for the execution itself.
+    pub durable_step_id: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.durable_step_id {
+            Some(step_id) => write!(f, "{} [{}]: {}", severity, step_id, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Checks a trace for inconsistencies between its status and timestamps.
+pub fn validate(trace: &DurableTrace) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut execution = |severity, message: String| {
+        findings.push(Finding {
+            severity,
+            durable_step_id: None,
+            message,
+        })
+    };
+
+    if !KNOWN_STATUSES.contains(&trace.status.as_str()) {
+        execution(
+            Severity::Warning,
+            format!("unknown status \"{}\"", trace.status),
+        );
+    }
+    match trace.completed_at {
+        Some(completed_at) if completed_at < trace.scheduled_at => execution(
+            Severity::Error,
+            "execution completed before it was scheduled".to_string(),
+        ),
+        Some(_) if trace.status == "running" => execution(
+            Severity::Warning,
+            "execution is running but has a completion time".to_string(),
+        ),
+        None if trace.status != "running" => execution(
+            Severity::Error,
+            format!("execution is {} but has no completion time", trace.status),
+        ),
+        _ => {}
+    }
+    if trace.status == "failed" && trace.failure_reason.is_none() {
+        execution(
+            Severity::Warning,
+            "execution failed without a failure reason".to_string(),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    for step in &trace.steps {
+        let mut step_finding = |severity, message: String| {
+            findings.push(Finding {
+                severity,
+                durable_step_id: Some(step.durable_step_id.clone()),
+                message,
+            })
+        };
+        if !seen.insert(step.durable_step_id.as_str()) {
+            step_finding(Severity::Warning, "duplicate step id".to_string());
+        }
+        if step.scheduled_at < trace.scheduled_at {
+            step_finding(
+                Severity::Warning,
+                "step was scheduled before the execution".to_string(),
+            );
+        }
+        match (step.completed_at, trace.completed_at) {
+            (Some(completed_at), _) if completed_at < step.scheduled_at => step_finding(
+                Severity::Error,
+                "step completed before it was scheduled".to_string(),
+            ),
+            (Some(completed_at), Some(execution_completed_at))
+                if completed_at > execution_completed_at =>
+            {
+                step_finding(
+                    Severity::Warning,
+                    "step completed after the execution".to_string(),
+                )
+            }
+            (None, _) if trace.status == "completed" => step_finding(
+                Severity::Warning,
+                "step never completed but the execution did".to_string(),
+            ),
+            _ => {}
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StepTrace, TaskInfo};
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_709_907_600, 0).unwrap() + TimeDelta::seconds(seconds)
+    }
+
+    fn step(id: &str, scheduled: i64, completed: Option<i64>) -> StepTrace {
+        let step = StepTrace::builder(
+            id,
+            at(scheduled),
+            TaskInfo::builder("task", "charge", at(scheduled)).build(),
+        );
+        match completed {
+            Some(completed) => step.completed_at(at(completed)),
+            None => step,
+        }
+        .build()
+    }
+
+    fn trace(status: &str, completed: Option<i64>, steps: Vec<StepTrace>) -> DurableTrace {
+        let trace = DurableTrace::builder("order", "exec-1", at(0))
+            .status(status)
+            .failure_reason("card declined")
+            .steps(steps);
+        match completed {
+            Some(completed) => trace.completed_at(at(completed)),
+            None => trace,
+        }
+        .build()
+    }
+
+    fn findings(trace: &DurableTrace) -> Vec<(Severity, Option<String>, String)> {
+        validate(trace)
+            .into_iter()
+            .map(|finding| (finding.severity, finding.durable_step_id, finding.message))
+            .collect()
+    }
+
+    fn execution(severity: Severity, message: &str) -> (Severity, Option<String>, String) {
+        (severity, None, message.to_string())
+    }
+
+    fn on_step(severity: Severity, message: &str) -> (Severity, Option<String>, String) {
+        (severity, Some("a".to_string()), message.to_string())
+    }
+
+    #[test]
+    fn consistent_trace_has_no_findings() {
+        let completed = trace("completed", Some(60), vec![step("a", 0, Some(30))]);
+        assert_eq!(validate(&completed), []);
+        let running = trace("running", None, vec![step("a", 0, None)]);
+        assert_eq!(validate(&running), []);
+    }
+
+    #[test]
+    fn unknown_status() {
+        let trace = trace("paused", Some(60), vec![]);
+        assert_eq!(
+            findings(&trace),
+            [execution(Severity::Warning, "unknown status \"paused\"")]
+        );
+    }
+
+    #[test]
+    fn execution_completed_before_scheduled() {
+        let trace = trace("completed", Some(-1), vec![]);
+        assert_eq!(
+            findings(&trace),
+            [execution(
+                Severity::Error,
+                "execution completed before it was scheduled"
+            )]
+        );
+    }
+
+    #[test]
+    fn running_with_completion_time() {
+        let trace = trace("running", Some(60), vec![]);
+        assert_eq!(
+            findings(&trace),
+            [execution(
+                Severity::Warning,
+                "execution is running but has a completion time"
+            )]
+        );
+    }
+
+    #[test]
+    fn finished_without_completion_time() {
+        let trace = trace("completed", None, vec![]);
+        assert_eq!(
+            findings(&trace),
+            [execution(
+                Severity::Error,
+                "execution is completed but has no completion time"
+            )]
+        );
+    }
+
+    #[test]
+    fn failed_without_reason() {
+        let mut trace = trace("failed", Some(60), vec![]);
+        trace.failure_reason = None;
+        assert_eq!(
+            findings(&trace),
+            [execution(
+                Severity::Warning,
+                "execution failed without a failure reason"
+            )]
+        );
+    }
+
+    #[test]
+    fn duplicate_step_id() {
+        let trace = trace(
+            "completed",
+            Some(60),
+            vec![step("a", 0, Some(10)), step("a", 10, Some(20))],
+        );
+        assert_eq!(
+            findings(&trace),
+            [on_step(Severity::Warning, "duplicate step id")]
+        );
+    }
+
+    #[test]
+    fn step_scheduled_before_execution() {
+        let trace = trace("completed", Some(60), vec![step("a", -5, Some(10))]);
+        assert_eq!(
+            findings(&trace),
+            [on_step(
+                Severity::Warning,
+                "step was scheduled before the execution"
+            )]
+        );
+    }
+
+    #[test]
+    fn step_completed_before_scheduled() {
+        let trace = trace("completed", Some(60), vec![step("a", 20, Some(10))]);
+        assert_eq!(
+            findings(&trace),
+            [on_step(
+                Severity::Error,
+                "step completed before it was scheduled"
+            )]
+        );
+    }
+
+    #[test]
+    fn step_completed_after_execution() {
+        let trace = trace("completed", Some(60), vec![step("a", 0, Some(90))]);
+        assert_eq!(
+            findings(&trace),
+            [on_step(
+                Severity::Warning,
+                "step completed after the execution"
+            )]
+        );
+    }
+
+    #[test]
+    fn step_never_completed() {
+        let trace = trace("completed", Some(60), vec![step("a", 0, None)]);
+        assert_eq!(
+            findings(&trace),
+            [on_step(
+                Severity::Warning,
+                "step never completed but the execution did"
+            )]
+        );
+    }
+
+    #[test]
+    fn display_names_the_step() {
+        let trace = trace("completed", Some(60), vec![step("a", 0, None)]);
+        assert_eq!(
+            validate(&trace)[0].to_string(),
+            "warning [a]: step never completed but the execution did"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Thin wrappers over browser APIs that have no Sycamore equivalent.
+
+use sycamore::prelude::{document, on_cleanup, on_mount, window, NodeRef};
+use sycamore::rt::console_error;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, HtmlCanvasElement, HtmlImageElement, Url};
+
+// How long an object url handed to a download stays valid.
+const REVOKE_DELAY_MS: i32 = 10_000;
+
+/// Reads the `value` of whatever form control fired `event`.
+pub(crate) fn event_target_value(event: &web_sys::Event) -> String {
+    event
+        .target()
+        .and_then(|target| js_sys::Reflect::get(&target, &JsValue::from_str("value")).ok())
+        .and_then(|value| value.as_string())
+        .unwrap_or_default()
+}
+
+/// Offers `contents` to the user as a file download.
+pub(crate) fn download_text(file_name: &str, mime_type: &str, contents: &str) {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let Ok(blob) = Blob::new_with_str_sequence_and_options(&parts, &options) else {
+        console_error!("Failed to create blob for {}", file_name);
+        return;
+    };
+    let Ok(url) = Url::create_object_url_with_blob(&blob) else {
+        console_error!("Failed to create object url for {}", file_name);
+        return;
+    };
+    download_url(file_name, &url);
+    // Firefox starts the download after the click returns and cancels it if the url is gone.
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    let _ = window().set_timeout_with_callback_and_timeout_and_arguments_0(
+        revoke.unchecked_ref(),
+        REVOKE_DELAY_MS,
+    );
+}
+
+/// Puts `text` on the system clipboard.
+pub(crate) fn copy_text(text: &str) {
+    // The returned promise only rejects when the page lacks focus or permission.
+    let _ = window().navigator().clipboard().write_text(text);
+}
+
+/// Offers whatever `href` points at (object or data url) as a file download.
+pub(crate) fn download_url(file_name: &str, href: &str) {
+    let anchor = document()
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+    if let Some(anchor) = anchor {
+        anchor.set_href(href);
+        anchor.set_download(file_name);
+        anchor.click();
+    }
+}
+
+/// Rasterises an SVG document through a canvas and downloads the result as a PNG.
+///
+/// `scale` multiplies the SVG's intrinsic size, so 2.0 gives a sharper image on HiDPI screens.
+pub(crate) fn download_svg_as_png(file_name: &str, svg: &str, width: f64, height: f64, scale: f64) {
+    let Ok(image) = HtmlImageElement::new() else {
+        return;
+    };
+    let file_name = file_name.to_string();
+    let loaded_image = image.clone();
+    let on_load = Closure::once(move || {
+        let canvas = document()
+            .create_element("canvas")
+            .ok()
+            .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok());
+        let Some(canvas) = canvas else {
+            return;
+        };
+        canvas.set_width((width * scale) as u32);
+        canvas.set_height((height * scale) as u32);
+        let context = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()
+            .and_then(|context| context.dyn_into::<web_sys::CanvasRenderingContext2d>().ok());
+        let Some(context) = context else {
+            console_error!("Canvas 2d context is not available");
+            return;
+        };
+        let _ = context.scale(scale, scale);
+        if context
+            .draw_image_with_html_image_element(&loaded_image, 0.0, 0.0)
+            .is_err()
+        {
+            console_error!("Failed to draw the timeline on the canvas");
+            return;
+        }
+        match canvas.to_data_url_with_type("image/png") {
+            Ok(url) => download_url(&file_name, &url),
+            Err(e) => console_error!("Failed to encode png: {:?}", e),
+        }
+    });
+    image.set_onload(Some(on_load.as_ref().unchecked_ref()));
+    // The image outlives this call, so the closure has to as well.
+    on_load.forget();
+    image.set_src(&format!(
+        "data:image/svg+xml;charset=utf-8,{}",
+        js_sys::encode_uri_component(svg)
+    ));
+}
+
+/// Calls `handler` for every key pressed on the page until the current reactive scope is disposed.
+pub(crate) fn on_window_keydown(handler: impl FnMut(web_sys::KeyboardEvent) + 'static) {
+    let handler = Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(handler);
+    let _ = window().add_event_listener_with_callback("keydown", handler.as_ref().unchecked_ref());
+    on_cleanup(move || {
+        let _ = window()
+            .remove_event_listener_with_callback("keydown", handler.as_ref().unchecked_ref());
+    });
+}
+
+/// Calls `handler` for every `event_type` event on the window until the current reactive scope is
+/// disposed.
+pub(crate) fn on_window_event(
+    event_type: &'static str,
+    handler: impl FnMut(web_sys::Event) + 'static,
+) {
+    let handler = Closure::<dyn FnMut(web_sys::Event)>::new(handler);
+    let _ = window().add_event_listener_with_callback(event_type, handler.as_ref().unchecked_ref());
+    on_cleanup(move || {
+        let _ = window()
+            .remove_event_listener_with_callback(event_type, handler.as_ref().unchecked_ref());
+    });
+}
+
+/// Distance from the top of the window to the top of `node`, if it is an element.
+pub(crate) fn element_top(node: &web_sys::Node) -> Option<f64> {
+    node.dyn_ref::<web_sys::Element>()
+        .map(|element| element.get_bounding_client_rect().top())
+}
+
+/// Height of the window's visible area.
+pub(crate) fn viewport_height() -> f64 {
+    window()
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64())
+        .unwrap_or_default()
+}
+
+/// Scrolls the page down by `pixels`, or up if negative.
+pub(crate) fn scroll_window_by(pixels: f64) {
+    window().scroll_by_with_x_and_y(0.0, pixels);
+}
+
+/// The 2d drawing context of `node`, if it is a canvas.
+pub(crate) fn canvas_context(node: &web_sys::Node) -> Option<web_sys::CanvasRenderingContext2d> {
+    node.dyn_ref::<HtmlCanvasElement>()?
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<web_sys::CanvasRenderingContext2d>().ok())
+}
+
+/// A theme colour such as `blue-600` as a CSS colour a canvas accepts, since canvases cannot read
+/// the variables the stylesheet uses.
+pub(crate) fn theme_colour(name: &str) -> String {
+    let channels = document()
+        .document_element()
+        .and_then(|root| window().get_computed_style(&root).ok().flatten())
+        .and_then(|style| style.get_property_value(&format!("--{}", name)).ok())
+        .unwrap_or_default();
+    format!("rgb({})", channels.trim())
+}
+
+/// How far down the clicked element `event` landed, from 0 at its top to 1 at its bottom.
+pub(crate) fn click_fraction_y(event: &web_sys::MouseEvent) -> Option<f64> {
+    let element = event
+        .current_target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())?;
+    let rect = element.get_bounding_client_rect();
+    (rect.height() > 0.0).then(|| (event.client_y() as f64 - rect.top()) / rect.height())
+}
+
+/// Upper-case tag name of the element `event` was fired at, e.g. `INPUT`.
+pub(crate) fn event_target_tag(event: &web_sys::Event) -> String {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .map(|element| element.tag_name())
+        .unwrap_or_default()
+}
+
+/// Moves keyboard focus to `node`, if it is an HTML element.
+pub(crate) fn focus(node: &web_sys::Node) {
+    if let Some(element) = node.dyn_ref::<web_sys::HtmlElement>() {
+        let _ = element.focus();
+    }
+}
+
+/// Takes keyboard focus away from the element `event` was fired at.
+pub(crate) fn blur_target(event: &web_sys::Event) {
+    if let Some(element) = event
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+    {
+        let _ = element.blur();
+    }
+}
+
+// Elements Tab can land on inside a dialog.
+const FOCUSABLE: &str = "a[href], button:not([disabled]), input:not([disabled]), select:not([disabled]), textarea:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
+/// Moves focus into `container` once it is mounted and hands it back to whatever had it before
+/// when the container goes away, as dialogs should.
+pub(crate) fn focus_while_mounted(container: NodeRef) {
+    let previous = document().active_element();
+    on_mount(move || {
+        let first = container
+            .try_get()
+            .and_then(|container| container.dyn_into::<web_sys::Element>().ok())
+            .and_then(|container| container.query_selector(FOCUSABLE).ok().flatten());
+        if let Some(first) = first {
+            focus(&first);
+        }
+    });
+    on_cleanup(move || {
+        if let Some(previous) = previous.filter(|previous| previous.is_connected()) {
+            focus(&previous);
+        }
+    });
+}
+
+/// Keeps Tab and Shift+Tab cycling inside the element the `keydown` listener is attached to.
+pub(crate) fn contain_tab(event: &web_sys::KeyboardEvent) {
+    if event.key() != "Tab" {
+        return;
+    }
+    let Some(container) = event
+        .current_target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+    else {
+        return;
+    };
+    let Ok(focusable) = container.query_selector_all(FOCUSABLE) else {
+        return;
+    };
+    let (Some(first), Some(last)) = (
+        focusable.item(0),
+        focusable.item(focusable.length().saturating_sub(1)),
+    ) else {
+        return;
+    };
+    let active = document().active_element().map(web_sys::Node::from);
+    let wrap_to = match (event.shift_key(), active) {
+        (true, Some(active)) if active == first => last,
+        (false, Some(active)) if active == last => first,
+        (_, Some(active)) if container.contains(Some(&active)) => return,
+        // Focus escaped the dialog somehow; bring it back.
+        _ => first,
+    };
+    event.prevent_default();
+    focus(&wrap_to);
+}
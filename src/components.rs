@@ -1,4 +1,13 @@
+mod annotations;
+pub mod command_palette;
+pub mod dashboard;
+mod export_menu;
+mod markers;
+mod settings_panel;
+mod shortcuts;
 mod step_detail;
+mod step_overview;
+mod step_table;
+mod stored_traces;
 pub mod trace;
 pub mod trace_input;
-mod util;
@@ -0,0 +1,69 @@
+use crate::ViewedTrace;
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::Annotation;
+use sycamore::prelude::*;
+
+fn now() -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(js_sys::Date::now() as i64).unwrap_or_default()
+}
+
+// Notes on the whole execution when `durable_step_id` is `None`, otherwise on that step.
+#[component(inline_props)]
+pub(crate) fn Annotations(#[prop(!optional)] durable_step_id: Option<String>) -> View {
+    let viewed = use_context::<ViewedTrace>();
+    let draft = create_signal(String::new());
+    let durable_step_id = create_signal(durable_step_id);
+    // Indexes into `DurableTrace::annotations`, so deleting removes the right note.
+    let notes = create_memo(move || {
+        viewed.trace.with(|trace| {
+            trace
+                .annotations
+                .iter()
+                .enumerate()
+                .filter(|(_, annotation)| {
+                    durable_step_id.with(|id| annotation.durable_step_id == *id)
+                })
+                .map(|(index, annotation)| (index, annotation.clone()))
+                .collect::<Vec<_>>()
+        })
+    });
+    let add = move |_| {
+        let text = draft.get_clone();
+        if text.trim().is_empty() {
+            return;
+        }
+        let annotation = Annotation::new(durable_step_id.get_clone(), text.trim(), now());
+        viewed.edit(|trace| trace.annotations.push(annotation));
+        draft.set(String::new());
+    };
+
+    view! {
+        div(class="space-y-2") {
+            ul(class="space-y-2") {
+                Indexed(list=notes, view=move |(index, annotation)| {
+                    let created_at_text = annotation.created_at.format("%Y-%m-%d %H:%M UTC").to_string();
+                    view! {
+                        li(class="flex items-start justify-between gap-2 bg-amber-50 border border-amber-200 rounded p-2") {
+                            div() {
+                                p(class="text-sm text-gray-900 whitespace-pre-wrap") { (annotation.text) }
+                                p(class="text-xs text-gray-500") { (created_at_text) }
+                            }
+                            button(on:click=move |_| viewed.edit(|trace| {
+                                    trace.annotations.remove(index);
+                                }),
+                                class="text-xs text-gray-500 hover:text-red-700") { "Delete" }
+                        }
+                    }
+                })
+            }
+            div(class="flex gap-2") {
+                textarea(bind:value=draft, rows="2", placeholder="Add a note",
+                    class="flex-1 rounded border border-gray-300 bg-white py-1 px-2 text-sm text-gray-700 outline-none focus:border-indigo-500 focus:ring-2 focus:ring-indigo-200")
+                button(on:click=add,
+                    class="self-end px-3 py-1 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
+                    "Add note"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use crate::browser;
+use crate::components::export_menu::FORMATS;
+use crate::settings::{Settings, Theme};
+use crate::storage::TraceStore;
+use crate::timeline::{StepLayout, StepSort, TimelineView};
+use crate::{Navigation, Page, State, Traces};
+use sycamore::prelude::*;
+
+// Long traces have thousands of steps; only the best matches are listed.
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    JumpToStep(usize),
+    FilterByTask(String),
+    OpenTrace(String),
+    CopyExport(usize),
+    DownloadExport(usize),
+    ShowDashboard,
+    ClearTrace,
+    ToggleRelativeTime,
+    SetTheme(Theme),
+    ToggleColourBlind,
+    Sort(StepSort),
+    Layout(StepLayout),
+    ZoomIn,
+    ZoomOut,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    group: &'static str,
+    title: String,
+    detail: String,
+    action: Action,
+}
+
+impl Command {
+    fn new(group: &'static str, title: impl Into<String>, action: Action) -> Self {
+        Command {
+            group,
+            title: title.into(),
+            detail: String::new(),
+            action,
+        }
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    // Matches on the title count for more than matches on the detail line.
+    fn score(&self, query: &str) -> Option<i32> {
+        let title = fuzzy_score(query, &self.title).map(|score| score * 2);
+        let detail = fuzzy_score(query, &self.detail);
+        title.max(detail)
+    }
+}
+
+/// Scores `text` by how well the characters of `query` appear in it in order, ignoring case.
+///
+/// Consecutive characters and characters at the start of a word score higher, so `ord` ranks
+/// `order-placed` above `create-record`. `None` when `query` is not a subsequence of `text`.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut previous_char = None;
+    let mut query_chars = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    for (i, c) in text.chars().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            score += 1;
+            if previous_match == Some(i.wrapping_sub(1)) {
+                score += 4;
+            }
+            if previous_char.is_none_or(|previous: char| !previous.is_alphanumeric()) {
+                score += 3;
+            }
+            previous_match = Some(i);
+            query_chars.next();
+        }
+        previous_char = Some(c);
+    }
+    query_chars.peek().is_none().then_some(score)
+}
+
+// App state the commands read and act on, taken from context when the palette is created since
+// commands run from event handlers.
+#[derive(Clone, Copy)]
+struct Palette {
+    state: State,
+    traces: Traces,
+    store: TraceStore,
+    navigation: Navigation,
+    timeline: TimelineView,
+    settings: Settings,
+}
+
+impl Palette {
+    fn new() -> Self {
+        Palette {
+            state: use_context::<State>(),
+            traces: use_context::<Traces>(),
+            store: use_context::<TraceStore>(),
+            navigation: use_context::<Navigation>(),
+            timeline: use_context::<TimelineView>(),
+            settings: use_context::<Settings>(),
+        }
+    }
+
+    // What can be done from where the user is: step and export commands need a trace on screen.
+    fn commands(self) -> Vec<Command> {
+        let Palette {
+            state,
+            traces,
+            store,
+            navigation,
+            settings,
+            ..
+        } = self;
+        let viewing_trace =
+            navigation.0.get_untracked() == Page::Trace && state.0.with_untracked(Option::is_some);
+
+        let mut commands = Vec::new();
+        if viewing_trace {
+            state.0.with_untracked(|trace| {
+                let steps = trace.iter().flat_map(|trace| trace.steps.iter());
+                let mut task_names: Vec<&str> = Vec::new();
+                for (index, step) in steps.enumerate() {
+                    let mut detail = step.in_task_info.task_name.clone();
+                    if let Some(out_task_info) = &step.out_task_info {
+                        detail = format!("{} → {}", detail, out_task_info.task_name);
+                    }
+                    commands.push(
+                        Command::new("Step", &step.durable_step_id, Action::JumpToStep(index))
+                            .detail(detail),
+                    );
+                    let tasks = [Some(&step.in_task_info), step.out_task_info.as_ref()];
+                    for info in tasks.into_iter().flatten() {
+                        if !task_names.contains(&info.task_name.as_str()) {
+                            task_names.push(&info.task_name);
+                        }
+                    }
+                }
+                for task_name in task_names {
+                    commands.push(
+                        Command::new(
+                            "Task",
+                            task_name,
+                            Action::FilterByTask(task_name.to_string()),
+                        )
+                        .detail("Filter the timeline to this task"),
+                    );
+                }
+            });
+            for (index, format) in FORMATS.iter().enumerate() {
+                commands.push(Command::new(
+                    "Export",
+                    format!("Download {}", format.label),
+                    Action::DownloadExport(index),
+                ));
+            }
+            // Sharing means handing over the trace itself, the first export format.
+            commands.push(
+                Command::new("Action", "Share trace", Action::CopyExport(0))
+                    .detail("Copy the trace JSON, notes and markers included, to the clipboard"),
+            );
+            let relative_title = if untrack(|| settings.precise_relative()) {
+                "Show absolute times"
+            } else {
+                "Show relative times (T+)"
+            };
+            commands.push(Command::new(
+                "View",
+                relative_title,
+                Action::ToggleRelativeTime,
+            ));
+            for layout in StepLayout::ALL {
+                commands.push(Command::new(
+                    "View",
+                    format!("Show steps as: {}", layout.label()),
+                    Action::Layout(layout),
+                ));
+            }
+            for sort in StepSort::ALL {
+                commands.push(Command::new(
+                    "View",
+                    format!("Sort steps: {}", sort.label()),
+                    Action::Sort(sort),
+                ));
+            }
+            commands.push(Command::new("View", "Zoom in", Action::ZoomIn));
+            commands.push(Command::new("View", "Zoom out", Action::ZoomOut));
+            commands.push(
+                Command::new("Action", "Clear trace", Action::ClearTrace)
+                    .detail("Close this trace and load another"),
+            );
+        }
+        for theme in Theme::ALL {
+            commands.push(Command::new(
+                "View",
+                format!("Theme: {}", theme.label()),
+                Action::SetTheme(theme),
+            ));
+        }
+        let colour_blind_title = if settings.colour_blind.get_untracked() {
+            "Use the standard colour palette"
+        } else {
+            "Use the colour-blind friendly palette"
+        };
+        commands.push(Command::new(
+            "View",
+            colour_blind_title,
+            Action::ToggleColourBlind,
+        ));
+        if traces.0.with_untracked(|traces| traces.len() > 1) {
+            commands.push(
+                Command::new("Action", "Compare traces", Action::ShowDashboard)
+                    .detail("Open the dashboard of all loaded traces"),
+            );
+        }
+        store.entries.with_untracked(|entries| {
+            for entry in entries.iter().rev() {
+                commands.push(
+                    Command::new(
+                        "Trace",
+                        &entry.name,
+                        Action::OpenTrace(entry.durable_execution_id.clone()),
+                    )
+                    .detail(&entry.durable_execution_id),
+                );
+            }
+        });
+        commands
+    }
+
+    fn run(self, action: Action) {
+        let Palette {
+            state,
+            traces,
+            navigation,
+            timeline,
+            settings,
+            ..
+        } = self;
+        match action {
+            Action::JumpToStep(index) => {
+                // A filter could be hiding the step.
+                timeline.filter.set(String::new());
+                timeline.selected.set(Some(index));
+            }
+            Action::FilterByTask(task_name) => timeline.filter.set(task_name),
+            Action::OpenTrace(durable_execution_id) => {
+                let trace = traces.0.with_untracked(|traces| {
+                    traces
+                        .iter()
+                        .find(|trace| trace.durable_execution_id == durable_execution_id)
+                        .cloned()
+                });
+                if trace.is_some() {
+                    state.0.set(trace);
+                    navigation.0.set(Page::Trace);
+                }
+            }
+            Action::CopyExport(index) => state.0.with_untracked(|trace| {
+                if let Some(trace) = trace {
+                    FORMATS[index].copy(trace, settings.time_base());
+                }
+            }),
+            Action::DownloadExport(index) => state.0.with_untracked(|trace| {
+                if let Some(trace) = trace {
+                    FORMATS[index].download(trace, settings.time_base());
+                }
+            }),
+            Action::ShowDashboard => navigation.0.set(Page::Dashboard),
+            Action::ClearTrace => state.0.set(None),
+            Action::ToggleRelativeTime => settings.toggle_precise_relative(),
+            Action::SetTheme(theme) => settings.theme.set(theme),
+            Action::ToggleColourBlind => settings
+                .colour_blind
+                .set(!settings.colour_blind.get_untracked()),
+            Action::Sort(sort) => timeline.sort.set(sort),
+            Action::Layout(layout) => timeline.layout.set(layout),
+            Action::ZoomIn => timeline.zoom_in(),
+            Action::ZoomOut => timeline.zoom_out(),
+        }
+    }
+}
+
+/// Opened with Ctrl+K (Cmd+K on macOS) from anywhere in the app.
+#[component]
+pub(crate) fn CommandPalette() -> View {
+    let palette = Palette::new();
+    let open = create_signal(false);
+    let query = create_signal(String::new());
+    let available = create_signal(Vec::new());
+    let highlighted = create_signal(0usize);
+
+    let show = move || {
+        available.set(palette.commands());
+        query.set(String::new());
+        highlighted.set(0);
+        open.set(true);
+    };
+    browser::on_window_keydown(move |event| {
+        if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k") {
+            event.prevent_default();
+            if open.get_untracked() {
+                open.set(false);
+            } else {
+                show();
+            }
+        }
+    });
+
+    let matches = create_memo(move || {
+        let query = query.get_clone();
+        available.with(|available| {
+            let mut scored = available
+                .iter()
+                .filter_map(|command| command.score(&query).map(|score| (score, command)))
+                .collect::<Vec<_>>();
+            // Stable, so equal scores keep the order commands were listed in.
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            scored
+                .into_iter()
+                .take(MAX_RESULTS)
+                .map(|(_, command)| command.clone())
+                .enumerate()
+                .collect::<Vec<_>>()
+        })
+    });
+    create_effect(move || {
+        query.track();
+        highlighted.set(0);
+    });
+    let run_highlighted = move || {
+        let command = matches.with(|matches| {
+            matches
+                .get(highlighted.get_untracked())
+                .map(|(_, command)| command.clone())
+        });
+        if let Some(command) = command {
+            open.set(false);
+            palette.run(command.action);
+        }
+    };
+    let on_keydown = move |event: web_sys::KeyboardEvent| {
+        let count = matches.with(Vec::len);
+        match event.key().as_str() {
+            "ArrowDown" if count > 0 => highlighted.set((highlighted.get() + 1) % count),
+            "ArrowUp" if count > 0 => highlighted.set((highlighted.get() + count - 1) % count),
+            "Enter" => run_highlighted(),
+            "Escape" => open.set(false),
+            _ => return,
+        }
+        event.prevent_default();
+        event.stop_propagation();
+    };
+    let active_option = move || {
+        if matches.with(Vec::is_empty) {
+            String::new()
+        } else {
+            format!("command-palette-option-{}", highlighted.get())
+        }
+    };
+
+    view! {
+        (if open.get() {
+            let dialog_ref = create_node_ref();
+            browser::focus_while_mounted(dialog_ref);
+            view! {
+                div(class="fixed z-50 inset-0 bg-black/60 px-4",
+                    on:click=move |_| open.set(false)) {
+                    div(r#ref=dialog_ref, role="dialog", aria-modal="true", aria-label="Command palette",
+                        on:keydown=|event: web_sys::KeyboardEvent| browser::contain_tab(&event),
+                        class="relative top-24 mx-auto max-w-xl rounded-md bg-white shadow-xl overflow-hidden",
+                        on:click=|event: web_sys::MouseEvent| event.stop_propagation()) {
+                        input(bind:value=query, on:keydown=on_keydown, r#type="text",
+                            role="combobox", aria-expanded="true", aria-controls="command-palette-results",
+                            aria-autocomplete="list", aria-activedescendant=active_option,
+                            aria-label="Command or search",
+                            placeholder="Jump to a step, task or trace, or run a command",
+                            class="w-full border-0 border-b border-gray-200 py-3 px-4 text-sm text-gray-900 outline-none")
+                        ul(id="command-palette-results", role="listbox", class="max-h-96 overflow-y-auto py-1") {
+                            (if matches.with(Vec::is_empty) {
+                                view! { li(role="presentation", class="px-4 py-2 text-sm text-gray-500") { "No matches" } }
+                            } else {
+                                view! {}
+                            })
+                            Keyed(list=matches, view=move |(position, command)| {
+                                let row_class = move || if highlighted.get() == position {
+                                    "flex items-baseline gap-3 px-4 py-2 cursor-pointer bg-indigo-50"
+                                } else {
+                                    "flex items-baseline gap-3 px-4 py-2 cursor-pointer"
+                                };
+                                let action = command.action.clone();
+                                view! {
+                                    li(class=row_class, role="option",
+                                        id=format!("command-palette-option-{}", position),
+                                        aria-selected=move || (highlighted.get() == position).to_string(),
+                                        on:mouseenter=move |_| highlighted.set(position),
+                                        on:click=move |_| {
+                                            open.set(false);
+                                            palette.run(action.clone());
+                                        }) {
+                                        span(class="w-14 shrink-0 text-xs font-medium text-gray-500") { (command.group) }
+                                        span(class="text-sm text-gray-900 truncate") { (command.title) }
+                                        span(class="ml-auto text-xs text-gray-500 truncate") { (command.detail) }
+                                    }
+                                }
+                            }, key=|(position, command)| (*position, command.title.clone()))
+                        }
+                    }
+                }
+            }
+        } else {
+            view! {}
+        })
+    }
+}
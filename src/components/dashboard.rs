@@ -0,0 +1,207 @@
+use crate::{Navigation, Page, Traces};
+use durable_trace::analysis::fine_duration;
+use durable_trace::analysis::fleet::{self, Bucket, Percentiles};
+use sycamore::prelude::*;
+
+// Rows shown in the failing steps table.
+const FAILING_STEPS_SHOWN: usize = 10;
+
+const TABLE: &str = "w-full text-sm text-left text-gray-700";
+const HEADER_CELL: &str = "py-1 pr-4 font-semibold text-gray-900";
+const CELL: &str = "py-1 pr-4";
+const NUMBER_CELL: &str = "py-1 pr-4 text-right tabular-nums";
+
+#[component(inline_props)]
+fn Section(title: &'static str, children: Children) -> View {
+    view! {
+        section(class="mb-8") {
+            h2(class="text-lg font-semibold text-gray-900 mb-2") { (title) }
+            (children)
+        }
+    }
+}
+
+#[component(inline_props)]
+fn Counts(counts: Vec<(String, usize)>) -> View {
+    let rows = counts
+        .into_iter()
+        .map(|(label, count)| {
+            view! {
+                tr(class="border-t") {
+                    td(class=CELL) { (label) }
+                    td(class=NUMBER_CELL) { (count) }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    view! {
+        table(class=TABLE) {
+            tbody() { (rows) }
+        }
+    }
+}
+
+fn percentile_cells(percentiles: Option<Percentiles>) -> View {
+    let cells = match percentiles {
+        Some(percentiles) => [
+            percentiles.p50,
+            percentiles.p95,
+            percentiles.p99,
+            percentiles.max,
+        ]
+        .map(fine_duration),
+        None => std::array::from_fn(|_| "-".to_string()),
+    };
+    cells
+        .into_iter()
+        .map(|text| view! { td(class=NUMBER_CELL) { (text) } })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+// Bars scaled to the fullest bucket, with the range and count in the tooltip.
+#[component(inline_props)]
+pub(crate) fn Histogram(buckets: Vec<Bucket>) -> View {
+    let fullest = buckets
+        .iter()
+        .map(|bucket| bucket.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bars = buckets
+        .into_iter()
+        .map(|bucket| {
+            let tooltip = format!(
+                "{} - {}: {}",
+                fine_duration(bucket.start),
+                fine_duration(bucket.end),
+                bucket.count
+            );
+            let height = bucket.count as f64 * 100.0 / fullest as f64;
+            view! {
+                div(class="flex-1 bg-blue-600", title=tooltip, style=format!("height: {}%;", height))
+            }
+        })
+        .collect::<Vec<_>>();
+    view! {
+        div(class="flex items-end gap-px h-8 w-40 bg-gray-100") { (bars) }
+    }
+}
+
+#[component]
+pub fn Dashboard() -> View {
+    let traces = use_context::<Traces>();
+    let navigation = use_context::<Navigation>();
+    let summary = traces.0.with(|traces| fleet::summarize(traces));
+
+    let workflow_rows = summary
+        .workflows
+        .into_iter()
+        .map(|workflow| {
+            view! {
+                tr(class="border-t") {
+                    td(class=CELL) { (workflow.name) }
+                    td(class=NUMBER_CELL) { (workflow.executions) }
+                    td(class=NUMBER_CELL) { (workflow.completed) }
+                    (percentile_cells(workflow.percentiles))
+                    td(class=CELL) { Histogram(buckets=workflow.histogram) }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let step_rows = summary
+        .steps
+        .into_iter()
+        .map(|step| {
+            view! {
+                tr(class="border-t") {
+                    td(class=CELL) { (step.durable_step_id) }
+                    td(class=NUMBER_CELL) { (step.occurrences) }
+                    (percentile_cells(step.percentiles))
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let failing_rows = summary
+        .failing_steps
+        .into_iter()
+        .take(FAILING_STEPS_SHOWN)
+        .map(|step| {
+            view! {
+                tr(class="border-t") {
+                    td(class=CELL) { (step.durable_step_id) }
+                    td(class=NUMBER_CELL) { (step.consecutive_failures) }
+                    td(class=NUMBER_CELL) { (step.failed_occurrences) }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let no_failures = failing_rows.is_empty();
+
+    view! {
+        div(class="bg-white border rounded-lg shadow-lg mx-auto mt-8 max-w-7xl py-4 sm:px-6 sm:py-12 lg:px-8") {
+            div(class="flex items-center justify-between mb-6") {
+                h1(class="text-2xl font-semibold text-gray-900") {
+                    "Dashboard (" (summary.traces) " traces)"
+                }
+                button(on:click=move |_| navigation.0.set(Page::Trace),
+                    class="px-4 py-2 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
+                    "Back"
+                }
+            }
+            div(class="grid grid-cols-1 md:grid-cols-2 gap-8") {
+                Section(title="Executions by status") { Counts(counts=summary.by_status) }
+                Section(title="Executions by name") { Counts(counts=summary.by_name) }
+            }
+            Section(title="Execution durations by workflow") {
+                table(class=TABLE) {
+                    thead() {
+                        tr() {
+                            th(class=HEADER_CELL) { "Name" }
+                            th(class=HEADER_CELL) { "Executions" }
+                            th(class=HEADER_CELL) { "Completed" }
+                            th(class=HEADER_CELL) { "p50" }
+                            th(class=HEADER_CELL) { "p95" }
+                            th(class=HEADER_CELL) { "p99" }
+                            th(class=HEADER_CELL) { "Max" }
+                            th(class=HEADER_CELL) { "Distribution" }
+                        }
+                    }
+                    tbody() { (workflow_rows) }
+                }
+            }
+            Section(title="Step durations") {
+                table(class=TABLE) {
+                    thead() {
+                        tr() {
+                            th(class=HEADER_CELL) { "Step id" }
+                            th(class=HEADER_CELL) { "Occurrences" }
+                            th(class=HEADER_CELL) { "p50" }
+                            th(class=HEADER_CELL) { "p95" }
+                            th(class=HEADER_CELL) { "p99" }
+                            th(class=HEADER_CELL) { "Max" }
+                        }
+                    }
+                    tbody() { (step_rows) }
+                }
+            }
+            Section(title="Most failing steps") {
+                (if no_failures {
+                    view! { p(class="text-sm text-gray-500") { "No step has failed." } }
+                } else {
+                    view! {}
+                })
+                table(class=TABLE) {
+                    thead() {
+                        tr() {
+                            th(class=HEADER_CELL) { "Step id" }
+                            th(class=HEADER_CELL) { "Consecutive failures" }
+                            th(class=HEADER_CELL) { "Failed occurrences" }
+                        }
+                    }
+                    tbody() { (failing_rows) }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::browser;
+use crate::settings::Settings;
+use crate::timeline::TimelineView;
+use crate::ViewedTrace;
+use durable_trace::export::csv::{self, CsvOptions, Delimiter, TimeFormat};
+use durable_trace::export::{chrome, markdown, mermaid, otlp, TimeBase};
+use durable_trace::DurableTrace;
+use sycamore::prelude::*;
+
+pub(super) struct ExportFormat {
+    pub(super) label: &'static str,
+    extension: &'static str,
+    mime_type: &'static str,
+    // OTLP and the trace itself keep absolute times, Chrome traces are always relative.
+    render: fn(&DurableTrace, TimeBase) -> String,
+}
+
+pub(super) const FORMATS: [ExportFormat; 5] = [
+    ExportFormat {
+        label: "Durable trace (JSON)",
+        extension: "json",
+        mime_type: "application/json",
+        render: durable_json,
+    },
+    ExportFormat {
+        label: "Markdown report",
+        extension: "md",
+        mime_type: "text/markdown",
+        render: markdown::report_with,
+    },
+    ExportFormat {
+        label: "Mermaid gantt",
+        extension: "mmd",
+        mime_type: "text/plain",
+        render: mermaid::gantt_with,
+    },
+    ExportFormat {
+        label: "OpenTelemetry (OTLP/JSON)",
+        extension: "otlp.json",
+        mime_type: "application/json",
+        render: |trace, _| otlp::export_json(trace),
+    },
+    ExportFormat {
+        label: "Chrome trace (Perfetto)",
+        extension: "trace.json",
+        mime_type: "application/json",
+        render: |trace, _| chrome::export_json(trace),
+    },
+];
+
+impl ExportFormat {
+    pub(super) fn copy(&self, trace: &DurableTrace, time_base: TimeBase) {
+        browser::copy_text(&(self.render)(trace, time_base));
+    }
+
+    pub(super) fn download(&self, trace: &DurableTrace, time_base: TimeBase) {
+        browser::download_text(
+            &format!("{}.{}", trace.durable_execution_id, self.extension),
+            self.mime_type,
+            &(self.render)(trace, time_base),
+        );
+    }
+}
+
+// The trace itself, annotations included, for sharing with someone else's viewer.
+fn durable_json(trace: &DurableTrace, _: TimeBase) -> String {
+    serde_json::to_string_pretty(trace).unwrap_or_default()
+}
+
+// Step rows as shown on the timeline, so the export follows the current filter and sort.
+#[component(inline_props)]
+fn StepTableExport<F>(trace: ReadSignal<DurableTrace>, on_done: F) -> View
+where
+    F: Fn() + Copy + 'static,
+{
+    let timeline = use_context::<TimelineView>();
+    let delimiter = create_signal(Delimiter::Comma);
+    let settings = use_context::<Settings>();
+    let origin = trace.with_untracked(|trace| trace.scheduled_at);
+    // Follows the relative time toggle until a format is picked here.
+    let picked_time_format = create_signal(None);
+    let time_format = move || {
+        picked_time_format
+            .get()
+            .unwrap_or(match settings.time_base() {
+                TimeBase::Absolute => TimeFormat::Iso,
+                TimeBase::Relative => TimeFormat::Relative(origin),
+            })
+    };
+    let time_format_key = move || match time_format() {
+        TimeFormat::Iso => "iso",
+        TimeFormat::EpochSeconds => "epoch-s",
+        TimeFormat::EpochMillis => "epoch-ms",
+        TimeFormat::Relative(_) => "relative",
+    };
+    let entry_button = "px-2 py-1 text-xs font-medium text-gray-700 border border-gray-300 rounded hover:bg-gray-50";
+    let entry_select = "rounded border border-gray-300 bg-white py-0.5 px-1 text-xs text-gray-700";
+
+    let render = move || {
+        let options = CsvOptions {
+            delimiter: delimiter.get(),
+            time_format: time_format(),
+        };
+        trace.with(|trace| {
+            let visible = timeline.visible_steps(&trace.steps);
+            csv::render(visible.iter().map(|index| &trace.steps[*index]), &options)
+        })
+    };
+    let copy = move |_| {
+        browser::copy_text(&render());
+        on_done();
+    };
+    let download = move |_| {
+        let file_name = trace.with(|trace| {
+            format!(
+                "{}-steps.{}",
+                trace.durable_execution_id,
+                delimiter.get().extension()
+            )
+        });
+        browser::download_text(&file_name, delimiter.get().mime_type(), &render());
+        on_done();
+    };
+    let on_delimiter_change = move |event: web_sys::Event| {
+        delimiter.set(match browser::event_target_value(&event).as_str() {
+            "tsv" => Delimiter::Tab,
+            _ => Delimiter::Comma,
+        })
+    };
+    let on_time_format_change = move |event: web_sys::Event| {
+        picked_time_format.set(Some(match browser::event_target_value(&event).as_str() {
+            "epoch-s" => TimeFormat::EpochSeconds,
+            "epoch-ms" => TimeFormat::EpochMillis,
+            "relative" => TimeFormat::Relative(origin),
+            _ => TimeFormat::Iso,
+        }))
+    };
+
+    view! {
+        li(class="px-4 py-2 space-y-2") {
+            div(class="flex items-center justify-between gap-4") {
+                span(class="text-sm text-gray-900") { "Step table" }
+                div(class="flex gap-2") {
+                    button(on:click=copy, class=entry_button) { "Copy" }
+                    button(on:click=download, class=entry_button) { "Download" }
+                }
+            }
+            div(class="flex gap-2") {
+                select(on:change=on_delimiter_change, class=entry_select) {
+                    option(value="csv") { "CSV" }
+                    option(value="tsv") { "TSV" }
+                }
+                select(on:change=on_time_format_change, prop:value=time_format_key, class=entry_select) {
+                    option(value="iso") { "ISO 8601 times" }
+                    option(value="epoch-s") { "Epoch seconds" }
+                    option(value="epoch-ms") { "Epoch milliseconds" }
+                    option(value="relative") { "Relative (T+)" }
+                }
+            }
+        }
+    }
+}
+
+#[component(inline_props)]
+pub(crate) fn ExportMenu() -> View {
+    // Read at click time so exports include annotations added since the trace was opened.
+    let trace = use_context::<ViewedTrace>().trace;
+    let settings = use_context::<Settings>();
+    let open = create_signal(false);
+    let entry_button = "px-2 py-1 text-xs font-medium text-gray-700 border border-gray-300 rounded hover:bg-gray-50";
+
+    let entries = FORMATS
+        .iter()
+        .map(|format| {
+            let copy = move |_| {
+                trace.with(|trace| format.copy(trace, settings.time_base()));
+                open.set(false);
+            };
+            let download = move |_| {
+                trace.with(|trace| format.download(trace, settings.time_base()));
+                open.set(false);
+            };
+            view! {
+                li(class="flex items-center justify-between gap-4 px-4 py-2") {
+                    span(class="text-sm text-gray-900") { (format.label) }
+                    div(class="flex gap-2") {
+                        button(on:click=copy, class=entry_button) { "Copy" }
+                        button(on:click=download, class=entry_button) { "Download" }
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        div(class="relative") {
+            button(
+                on:click=move |_| open.set(!open.get()),
+                aria-haspopup="true", aria-expanded=move || open.get().to_string(),
+                class="inline-flex items-center gap-2 px-4 py-2 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
+                "Export"
+            }
+            ul(class="absolute right-0 z-10 mt-2 w-72 bg-white border border-gray-200 rounded-md shadow-lg divide-y divide-gray-100",
+                style=move || if open.get() { "" } else { "display: none;" }) {
+                (entries)
+                StepTableExport(trace=*trace, on_done=move || open.set(false))
+            }
+        }
+    }
+}
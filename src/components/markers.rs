@@ -0,0 +1,181 @@
+use crate::settings::Settings;
+use crate::ViewedTrace;
+use durable_trace::analysis;
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::import::markers;
+use durable_trace::Marker;
+use sycamore::prelude::*;
+
+const KINDS: [&str; 4] = ["deploy", "alert", "outage", "other"];
+
+// Line or border, band fill and label colours for a marker kind.
+fn colors(kind: Option<&str>) -> (&'static str, &'static str, &'static str) {
+    match kind {
+        Some("deploy") => ("border-indigo-500", "bg-indigo-500/20", "text-indigo-700"),
+        Some("alert") => ("border-amber-500", "bg-amber-500/20", "text-amber-700"),
+        Some("outage") => ("border-red-500", "bg-red-500/20", "text-red-700"),
+        _ => ("border-gray-500", "bg-gray-500/20", "text-gray-700"),
+    }
+}
+
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+// Vertical lines for point markers and bands for ranges, drawn over the step bars.
+#[component(inline_props)]
+pub(crate) fn MarkerOverlay(window_start: DateTime<Utc>, window_end: DateTime<Utc>) -> View {
+    let viewed = use_context::<ViewedTrace>();
+    let visible = create_memo(move || {
+        viewed.trace.with(|trace| {
+            trace
+                .markers
+                .iter()
+                .filter_map(|marker| {
+                    let position = analysis::marker_position(marker, window_start, window_end);
+                    let in_window = position.left <= 100.0 && position.left + position.width >= 0.0;
+                    in_window.then(|| (marker.clone(), position))
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    view! {
+        // Decorative, the markers are listed with their times under "Markers".
+        div(class="absolute inset-0 z-20 pointer-events-none", aria-hidden="true") {
+            Indexed(list=visible, view=|(marker, position)| {
+                let (border, band, text) = colors(marker.kind.as_deref());
+                let left = position.left.max(0.0);
+                let width = (position.left + position.width).min(100.0) - left;
+                let (class, style) = match marker.end {
+                    Some(_) => (
+                        format!("absolute top-0 bottom-0 border-x {} {}", border, band),
+                        format!("left: {}%; width: {}%;", left, width),
+                    ),
+                    None => (
+                        format!("absolute top-0 bottom-0 border-l-2 border-dashed {}", border),
+                        format!("left: {}%;", left),
+                    ),
+                };
+                let label_class = format!("absolute top-0 left-1 px-1 text-xs whitespace-nowrap bg-white/80 {}", text);
+                let label = marker.name.clone();
+                view! {
+                    div(class=class, style=style, title=marker.name) {
+                        span(class=label_class) { (label) }
+                    }
+                }
+            })
+        }
+    }
+}
+
+// Adds markers by hand or from a pasted JSON list, and lists the ones on the trace.
+#[component]
+pub(crate) fn MarkerEditor() -> View {
+    let viewed = use_context::<ViewedTrace>();
+    let name = create_signal(String::new());
+    let kind = create_signal(KINDS[0].to_string());
+    let start = create_signal(String::new());
+    let end = create_signal(String::new());
+    let import_json = create_signal(String::new());
+    let error = create_signal(None::<String>);
+
+    let add = move |_| {
+        let Some(start_at) = parse_time(&start.get_clone()) else {
+            error.set(Some(
+                "Start must be an RFC 3339 time such as 2024-03-08T14:20:00Z".to_string(),
+            ));
+            return;
+        };
+        let end_text = end.get_clone();
+        let end_at = match end_text.trim() {
+            "" => None,
+            text => match parse_time(text) {
+                Some(end_at) if end_at >= start_at => Some(end_at),
+                _ => {
+                    error.set(Some(
+                        "End must be an RFC 3339 time after the start".to_string(),
+                    ));
+                    return;
+                }
+            },
+        };
+        let marker = Marker {
+            name: name.get_clone().trim().to_string(),
+            start: start_at,
+            end: end_at,
+            kind: Some(kind.get_clone()),
+        };
+        viewed.edit(|trace| trace.markers.push(marker));
+        name.set(String::new());
+        start.set(String::new());
+        end.set(String::new());
+        error.set(None);
+    };
+    let import = move |_| match markers::parse(&import_json.get_clone()) {
+        Ok(imported) => {
+            viewed.edit(|trace| trace.markers.extend(imported));
+            import_json.set(String::new());
+            error.set(None);
+        }
+        Err(e) => error.set(Some(e.to_string())),
+    };
+    let settings = use_context::<Settings>();
+    let rows = create_memo(move || viewed.trace.with(|trace| trace.markers.clone()));
+    let kind_options = KINDS
+        .into_iter()
+        .map(|kind| view! { option(value=kind) { (kind) } })
+        .collect::<Vec<_>>();
+    let input = "rounded border border-gray-300 bg-white py-1 px-2 text-sm text-gray-700 outline-none focus:border-indigo-500 focus:ring-2 focus:ring-indigo-200";
+    let button = "px-3 py-1 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50";
+
+    view! {
+        div(class="space-y-2") {
+            ul(class="space-y-1") {
+                Indexed(list=rows, view=move |marker| {
+                    let (start, end) = (marker.start, marker.end);
+                    let range = move || {
+                        let origin = viewed.trace.with(|trace| trace.scheduled_at);
+                        match end {
+                            Some(end) => format!("{} - {}", settings.format_time(start, origin), settings.format_time(end, origin)),
+                            None => settings.format_time(start, origin),
+                        }
+                    };
+                    let kind = marker.kind.clone().unwrap_or_default();
+                    let label = marker.name.clone();
+                    let remove = move |_| {
+                        let marker = marker.clone();
+                        viewed.edit(move |trace| {
+                            if let Some(index) = trace.markers.iter().position(|other| *other == marker) {
+                                trace.markers.remove(index);
+                            }
+                        })
+                    };
+                    view! {
+                        li(class="flex items-center justify-between gap-2 text-sm text-gray-700") {
+                            span() { (kind) ": " (label) " (" (range) ")" }
+                            button(on:click=remove, class="text-xs text-gray-500 hover:text-red-700") { "Delete" }
+                        }
+                    }
+                })
+            }
+            div(class="flex flex-wrap gap-2") {
+                input(bind:value=name, placeholder="Name", class=input)
+                select(bind:value=kind, class=input) { (kind_options) }
+                input(bind:value=start, placeholder="Start (RFC 3339)", class=input)
+                input(bind:value=end, placeholder="End, for ranges", class=input)
+                button(on:click=add, class=button) { "Add marker" }
+            }
+            div(class="flex gap-2") {
+                textarea(bind:value=import_json, rows="2",
+                    placeholder="[{\"name\": \"deploy v42\", \"at\": \"2024-03-08T14:20:00Z\", \"kind\": \"deploy\"}]",
+                    class=format!("flex-1 font-mono {}", input))
+                button(on:click=import, class=format!("self-end {}", button)) { "Import JSON" }
+            }
+            (error.get_clone().map(|error| view! {
+                p(class="text-sm text-red-700") { (error) }
+            }))
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::browser;
+use crate::settings::{ClockFormat, RelativeFormat, Settings, Theme, TimeZoneSetting};
+use sycamore::prelude::*;
+
+#[component(inline_props)]
+pub(crate) fn SettingsPanel() -> View {
+    let settings = use_context::<Settings>();
+    let open = create_signal(false);
+    let entry_select = "rounded border border-gray-300 bg-white py-0.5 px-1 text-xs text-gray-700";
+
+    // The IANA name being typed, kept while switching to local or UTC and back.
+    let zone_name = create_signal(match settings.zone.get_clone_untracked() {
+        TimeZoneSetting::Named(name) => name,
+        _ => String::new(),
+    });
+    let zone_key = move || match settings.zone.with(|zone| zone.clone()) {
+        TimeZoneSetting::Local => "local",
+        TimeZoneSetting::Utc => "utc",
+        TimeZoneSetting::Named(_) => "named",
+    };
+    let on_zone_change = move |event: web_sys::Event| {
+        settings
+            .zone
+            .set(match browser::event_target_value(&event).as_str() {
+                "utc" => TimeZoneSetting::Utc,
+                "named" => TimeZoneSetting::Named(zone_name.get_clone()),
+                _ => TimeZoneSetting::Local,
+            })
+    };
+    let on_zone_name_change = move |event: web_sys::Event| {
+        let name = browser::event_target_value(&event).trim().to_string();
+        zone_name.set(name.clone());
+        settings.zone.set(TimeZoneSetting::Named(name));
+    };
+    let on_clock_change = move |event: web_sys::Event| {
+        settings
+            .clock
+            .set(match browser::event_target_value(&event).as_str() {
+                "12h" => ClockFormat::TwelveHour,
+                _ => ClockFormat::TwentyFourHour,
+            })
+    };
+    let clock_key = move || match settings.clock.get() {
+        ClockFormat::TwentyFourHour => "24h",
+        ClockFormat::TwelveHour => "12h",
+    };
+    let on_relative_format_change = move |event: web_sys::Event| {
+        settings
+            .relative_format
+            .set(RelativeFormat::from_key(&browser::event_target_value(
+                &event,
+            )))
+    };
+    let relative_format_options = RelativeFormat::ALL
+        .into_iter()
+        .map(|format| view! { option(value=format.key()) { (format.label()) } })
+        .collect::<Vec<_>>();
+    let on_theme_change = move |event: web_sys::Event| {
+        settings
+            .theme
+            .set(Theme::from_key(&browser::event_target_value(&event)))
+    };
+    let theme_options = Theme::ALL
+        .into_iter()
+        .map(|theme| view! { option(value=theme.key()) { (theme.label()) } })
+        .collect::<Vec<_>>();
+
+    view! {
+        div(class="relative") {
+            button(
+                on:click=move |_| open.set(!open.get()),
+                aria-haspopup="true", aria-expanded=move || open.get().to_string(),
+                class="inline-flex items-center gap-2 px-4 py-2 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
+                "Settings"
+            }
+            div(class="absolute right-0 z-10 mt-2 w-72 p-4 space-y-3 bg-white border border-gray-200 rounded-md shadow-lg",
+                style=move || if open.get() { "" } else { "display: none;" }) {
+                label(class="flex items-center justify-between gap-4 text-sm text-gray-900") {
+                    "Time zone"
+                    select(on:change=on_zone_change, prop:value=zone_key, class=entry_select) {
+                        option(value="local") { "Local" }
+                        option(value="utc") { "UTC" }
+                        option(value="named") { "Other (IANA)" }
+                    }
+                }
+                (if zone_key() == "named" {
+                    view! {
+                        input(r#type="text", placeholder="Europe/Lisbon",
+                            prop:value=zone_name.get_clone(),
+                            on:change=on_zone_name_change,
+                            class="w-full rounded border border-gray-300 py-1 px-2 text-xs text-gray-700")
+                    }
+                } else {
+                    view! {}
+                })
+                (match settings.zone_error() {
+                    Some(error) => view! { p(class="text-xs text-red-600") { (error) } },
+                    None => view! {},
+                })
+                label(class="flex items-center justify-between gap-4 text-sm text-gray-900") {
+                    "Clock"
+                    select(on:change=on_clock_change, prop:value=clock_key, class=entry_select) {
+                        option(value="24h") { "24-hour" }
+                        option(value="12h") { "12-hour" }
+                    }
+                }
+                label(class="flex items-center gap-2 text-sm text-gray-900") {
+                    input(r#type="checkbox", bind:checked=settings.relative)
+                    "Relative to execution start"
+                }
+                label(class="flex items-center justify-between gap-4 text-sm text-gray-900") {
+                    "Relative format"
+                    select(on:change=on_relative_format_change,
+                        prop:value=move || settings.relative_format.get().key(),
+                        disabled=move || !settings.relative.get(), class=entry_select) {
+                        (relative_format_options)
+                    }
+                }
+                hr()
+                label(class="flex items-center justify-between gap-4 text-sm text-gray-900") {
+                    "Theme"
+                    select(on:change=on_theme_change, prop:value=move || settings.theme.get().key(), class=entry_select) {
+                        (theme_options)
+                    }
+                }
+                label(class="flex items-center gap-2 text-sm text-gray-900") {
+                    input(r#type="checkbox", bind:checked=settings.colour_blind)
+                    "Colour-blind friendly palette"
+                }
+            }
+        }
+    }
+}
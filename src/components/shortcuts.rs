@@ -0,0 +1,55 @@
+use crate::browser;
+use sycamore::prelude::*;
+
+/// Keys handled by the steps timeline and the app, as shown in the cheat sheet.
+const SHORTCUTS: [(&str, &str); 8] = [
+    ("↑ / ↓", "Select the previous or next step"),
+    ("Enter", "Open the selected step's details"),
+    ("Esc", "Close the details panel or this sheet"),
+    ("/", "Focus the step filter"),
+    ("+", "Zoom in"),
+    ("-", "Zoom out"),
+    ("?", "Show or hide this sheet"),
+    ("Ctrl+K", "Open the command palette"),
+];
+
+#[component(inline_props)]
+pub(crate) fn ShortcutHelp<F>(on_close: F) -> View
+where
+    F: Fn() + Copy + 'static,
+{
+    let rows = SHORTCUTS
+        .into_iter()
+        .map(|(keys, action)| {
+            view! {
+                tr() {
+                    td(class="py-1 pr-4") {
+                        kbd(class="px-2 py-0.5 rounded border border-gray-300 bg-gray-50 font-mono text-xs text-gray-800") { (keys) }
+                    }
+                    td(class="py-1 text-sm text-gray-700") { (action) }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let dialog_ref = create_node_ref();
+    browser::focus_while_mounted(dialog_ref);
+    view! {
+        div(class="fixed z-50 inset-0 bg-black/60 px-4", on:click=move |_| on_close()) {
+            div(r#ref=dialog_ref, role="dialog", aria-modal="true", aria-labelledby="shortcuts-title",
+                on:keydown=|event: web_sys::KeyboardEvent| browser::contain_tab(&event),
+                class="relative top-40 mx-auto max-w-md rounded-md bg-white p-6 shadow-xl",
+                on:click=|event: web_sys::MouseEvent| event.stop_propagation()) {
+                div(class="flex items-center justify-between mb-4") {
+                    h2(id="shortcuts-title", class="text-lg font-semibold text-gray-900") { "Keyboard shortcuts" }
+                    button(on:click=move |_| on_close(),
+                        class="px-2 py-1 text-xs font-medium text-gray-700 border border-gray-300 rounded hover:bg-gray-50") {
+                        "Close"
+                    }
+                }
+                table(class="w-full") {
+                    tbody() { (rows) }
+                }
+            }
+        }
+    }
+}
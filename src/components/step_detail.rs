@@ -1,23 +1,65 @@
-use crate::StepTrace;
+use crate::browser;
+use crate::components::annotations::Annotations;
+use crate::components::dashboard::Histogram;
+use crate::settings::Settings;
+use crate::ViewedTrace;
+use durable_trace::analysis::outliers::Outlier;
+use durable_trace::StepTrace;
 use sycamore::prelude::*;
 
 // h parameter is the callback to hide the sidepanel
 #[component(inline_props)]
-pub(crate) fn StepDetail<F>(step_trace: StepTrace, on_hide_step: F) -> View
+pub(crate) fn StepDetail<F>(
+    step_trace: StepTrace,
+    #[prop(!optional)] outlier: Option<Outlier>,
+    on_hide_step: F,
+) -> View
 where
     F: Fn() + Copy + 'static,
 {
-    let completed_at_text = step_trace.completed_at_text();
+    // Relative times count from the start of the execution, as on the timeline.
+    let settings = use_context::<Settings>();
+    let origin = use_context::<ViewedTrace>()
+        .trace
+        .with_untracked(|trace| trace.scheduled_at);
+    let (scheduled_at, completed_at) = (step_trace.scheduled_at, step_trace.completed_at);
+    let scheduled_at_text = move || settings.format_time(scheduled_at, origin);
+    let completed_at_text = move || {
+        completed_at
+            .map(|completed_at| settings.format_time(completed_at, origin))
+            .unwrap_or_else(|| "-".to_string())
+    };
     let in_task_json_text = step_trace.in_task_text();
     let out_task_json_text = step_trace.out_task_text();
     let result_text = step_trace.result.unwrap_or("-".to_string());
+    let note_step_id = step_trace.durable_step_id.clone();
+    let outlier_view = match outlier {
+        Some(outlier) => {
+            let explanation = outlier.explanation();
+            view! {
+                div(class="bg-amber-50 border border-amber-300 rounded-lg p-4") {
+                    h3(class="text-sm font-medium text-amber-800") { "Unusual duration" }
+                    p(class="mt-1 text-sm text-gray-900") { (explanation) }
+                    div(class="mt-2") {
+                        Histogram(buckets=outlier.baseline.histogram)
+                    }
+                }
+            }
+        }
+        None => view! {},
+    };
+    let panel_ref = create_node_ref();
+    browser::focus_while_mounted(panel_ref);
     view! {
-        div(class="fixed inset-y-0 right-0 w-full max-w-xl bg-white shadow-xl") {
+        div(r#ref=panel_ref, role="dialog", aria-modal="true", aria-labelledby="step-detail-title",
+            on:keydown=|event: web_sys::KeyboardEvent| browser::contain_tab(&event),
+            class="fixed inset-y-0 right-0 w-full max-w-xl bg-white shadow-xl") {
             div(class="h-full flex flex-col") {
                 div(class="flex items-center justify-between px-6 py-4 border-b border-gray-200") {
-                  h2(class="text-lg font-semibold text-gray-900") { "Step Details" }
+                  h2(id="step-detail-title", class="text-lg font-semibold text-gray-900") { "Step Details" }
                     button(
                         on:click=move |_| on_hide_step(),
+                        aria-label="Close step details",
                         class="rounded-md text-gray-400 hover:text-gray-500 focus:outline-none focus:ring-2 focus:ring-blue-500") {
                         svg(
                         xmlns="http://www.w3.org/2000/svg", width="24", height="24", viewBox="0 0 24 24", fill="none",
@@ -31,14 +73,19 @@ where
 
                 div(class="flex-1 overflow-y-auto p-6") {
                     div(class="space-y-6") {
+                        (outlier_view)
                         div() {
                             h3(class="text-sm font-medium text-gray-500") { "Step ID" }
                             p(class="mt-1 text-sm text-gray-900") { (step_trace.durable_step_id) }
                         }
+                        div() {
+                            h3(class="text-sm font-medium text-gray-500 mb-2") { "Notes" }
+                            Annotations(durable_step_id=Some(note_step_id))
+                        }
                         div(class="grid grid-cols-2 gap-4") {
                             div() {
                                 h3(class="text-sm font-medium text-gray-500") { "Scheduled At" }
-                                p(class="mt-1 text-sm text-gray-900") { (step_trace.scheduled_at.to_rfc3339()) }
+                                p(class="mt-1 text-sm text-gray-900") { (scheduled_at_text) }
                             }
                             div() {
                                 h3(class="text-sm font-medium text-gray-500") { "Completed At" }
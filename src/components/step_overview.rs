@@ -0,0 +1,88 @@
+use crate::browser;
+use crate::settings::Settings;
+use crate::viewport::Viewport;
+use durable_trace::analysis;
+use durable_trace::analysis::outliers::Outlier;
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::StepTrace;
+use sycamore::prelude::*;
+
+// Drawing size of the canvas; CSS stretches it to the width of the timeline.
+const CANVAS_WIDTH: f64 = 1000.0;
+const CANVAS_HEIGHT: f64 = 80.0;
+
+/// Every step in the current filter and order as a sliver on one canvas, with the part mounted
+/// below outlined. Clicking scrolls the list there. Costs one element however long the trace is.
+#[component(inline_props)]
+pub(crate) fn StepOverview<O>(
+    steps: ReadSignal<Vec<StepTrace>>,
+    visible: ReadSignal<Vec<usize>>,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    outlier: O,
+    viewport: Viewport,
+) -> View
+where
+    O: Fn(&StepTrace) -> Option<Outlier> + Copy + 'static,
+{
+    let settings = use_context::<Settings>();
+    let canvas_ref = create_node_ref();
+    let band_style = move || {
+        let (top, bottom) = viewport.visible_fraction(visible.with(Vec::len));
+        format!(
+            "top: {}%; height: {}%;",
+            top * 100.0,
+            (bottom - top) * 100.0
+        )
+    };
+    let on_click = move |event: web_sys::MouseEvent| {
+        if let Some(fraction) = browser::click_fraction_y(&event) {
+            viewport.centre_on(fraction.clamp(0.0, 1.0) * visible.with(Vec::len) as f64);
+        }
+    };
+    let view = view! {
+        // Decorative: the rows themselves are announced with their position in the list.
+        div(class="relative mb-6 rounded-lg bg-gray-100 cursor-pointer", aria-hidden="true", title="Overview of all steps, click to jump") {
+            canvas(r#ref=canvas_ref, on:click=on_click, class="block w-full h-20",
+                width=CANVAS_WIDTH.to_string(), height=CANVAS_HEIGHT.to_string())
+            div(class="absolute inset-x-0 border-2 border-indigo-500 rounded pointer-events-none", style=band_style)
+        }
+    };
+    create_effect(move || {
+        // Colours come from the theme, so they are read again when it changes.
+        settings.theme.track();
+        settings.colour_blind.track();
+        let Some(context) = canvas_ref
+            .try_get()
+            .and_then(|canvas| browser::canvas_context(&canvas))
+        else {
+            return;
+        };
+        context.clear_rect(0.0, 0.0, CANVAS_WIDTH, CANVAS_HEIGHT);
+        let (bar, unusual) = (
+            browser::theme_colour("blue-600"),
+            browser::theme_colour("amber-500"),
+        );
+        visible.with(|visible| {
+            steps.with(|steps| {
+                let row_height = CANVAS_HEIGHT / visible.len().max(1) as f64;
+                for (position, step) in visible.iter().map(|index| &steps[*index]).enumerate() {
+                    let bar_position = analysis::bar_position(step, window_start, window_end);
+                    let colour = if outlier(step).is_some() {
+                        &unusual
+                    } else {
+                        &bar
+                    };
+                    context.set_fill_style_str(colour);
+                    context.fill_rect(
+                        bar_position.left * CANVAS_WIDTH / 100.0,
+                        position as f64 * row_height,
+                        (bar_position.width * CANVAS_WIDTH / 100.0).max(1.0),
+                        row_height.max(1.0),
+                    );
+                }
+            })
+        });
+    });
+    view
+}
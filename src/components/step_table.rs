@@ -0,0 +1,152 @@
+use crate::settings::Settings;
+use crate::timeline::TimelineView;
+use crate::viewport::Viewport;
+use durable_trace::analysis;
+use durable_trace::analysis::outliers::Outlier;
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::StepTrace;
+use sycamore::prelude::*;
+
+/// Rows are one line tall and have no borders, so every one is exactly this high and only the
+/// ones on screen need mounting.
+pub(crate) const TABLE_ROW_HEIGHT: f64 = 32.0;
+// Columns, for the spacer rows standing in for the unmounted ones.
+const COLUMNS: usize = 9;
+
+#[component(inline_props)]
+fn StepRow<F>(
+    index: usize,
+    position: usize,
+    window_start: DateTime<Utc>,
+    step: StepTrace,
+    #[prop(!optional)] outlier: Option<Outlier>,
+    on_show_step: F,
+) -> View
+where
+    F: Fn(StepTrace) + Copy + 'static,
+{
+    let settings = use_context::<Settings>();
+    let timeline = use_context::<TimelineView>();
+    let (scheduled_at, completed_at) = (step.scheduled_at, step.completed_at);
+    let scheduled_at_text = move || settings.format_time(scheduled_at, window_start);
+    let completed_at_text = move || {
+        completed_at
+            .map(|completed_at| settings.format_time(completed_at, window_start))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let duration_text = analysis::step_duration(&step)
+        .map(analysis::fine_duration)
+        .unwrap_or_else(|| "Not completed".to_string());
+    let task_text = match &step.out_task_info {
+        Some(out_task_info) => format!(
+            "{} → {}",
+            step.in_task_info.task_name, out_task_info.task_name
+        ),
+        None => step.in_task_info.task_name.clone(),
+    };
+    let outlier_text = outlier
+        .map(|outlier| outlier.explanation())
+        .unwrap_or_default();
+    let details_label = format!("View details for {}", step.durable_step_id);
+    let step_id = step.durable_step_id.clone();
+    let attempt = step.in_task_info.execution_version;
+    let failures = step.in_task_info.consecutive_failures;
+
+    // Follows the keyboard selection like the timeline rows do; striped by position in the
+    // whole list, since the mounted rows start at different ones.
+    let is_selected = create_selector(move || timeline.selected.get() == Some(index));
+    let row_class = move || match (is_selected.get(), position % 2) {
+        (true, _) => "whitespace-nowrap bg-indigo-50",
+        (false, 1) => "whitespace-nowrap bg-gray-50",
+        (false, _) => "whitespace-nowrap",
+    };
+    let on_show = move |_| {
+        timeline.selected.set(Some(index));
+        on_show_step(step.clone());
+    };
+    view! {
+        // Row 1 is the header.
+        tr(class=row_class, style=format!("height: {}px;", TABLE_ROW_HEIGHT),
+            aria-rowindex=(position + 2).to_string(),
+            aria-current=move || if is_selected.get() { "true" } else { "false" }) {
+            th(scope="row", class="py-1 pr-2 text-left font-medium text-gray-900") { (step_id) }
+            td(class="py-1 pr-2 text-gray-700") { (task_text) }
+            td(class="py-1 pr-2 text-gray-700 tabular-nums") { (scheduled_at_text) }
+            td(class="py-1 pr-2 text-gray-700 tabular-nums") { (completed_at_text) }
+            td(class="py-1 pr-2 text-right text-gray-700 tabular-nums") { (duration_text) }
+            td(class="py-1 pr-2 text-right text-gray-700 tabular-nums") { (attempt) }
+            td(class="py-1 pr-2 text-right text-gray-700 tabular-nums") { (failures) }
+            td(class="py-1 pr-2 text-amber-800") { (outlier_text) }
+            td(class="py-1 text-right") {
+                button(on:click=on_show, aria-label=details_label,
+                    class="px-2 py-0.5 text-xs font-medium text-gray-700 border border-gray-300 rounded hover:bg-gray-50") {
+                    "Details"
+                }
+            }
+        }
+    }
+}
+
+/// The visible steps as a table, an alternative to the bars for screen readers and keyboards.
+#[component(inline_props)]
+pub(crate) fn StepTable<F, O>(
+    steps: ReadSignal<Vec<StepTrace>>,
+    visible: ReadSignal<Vec<usize>>,
+    window_start: DateTime<Utc>,
+    outlier: O,
+    on_show_step: F,
+    viewport: Viewport,
+) -> View
+where
+    F: Fn(StepTrace) + Copy + 'static,
+    O: Fn(&StepTrace) -> Option<Outlier> + Copy + 'static,
+{
+    let heading = "py-1 pr-2 font-medium text-gray-700";
+    let count = create_memo(move || visible.with(Vec::len));
+    let mounted = viewport.mounted(steps, visible);
+    // The mounted rows are consecutive, so the spacers cover everything before and after them.
+    let spacer = |rows: usize| format!("height: {}px;", rows as f64 * TABLE_ROW_HEIGHT);
+    let above = move || {
+        spacer(mounted.with(|mounted| mounted.first().map_or(0, |(position, _, _)| *position)))
+    };
+    let below = move || {
+        spacer(mounted.with(|mounted| {
+            mounted
+                .last()
+                .map_or(count.get(), |(position, _, _)| count.get() - position - 1)
+        }))
+    };
+    let list_ref = viewport.list_ref();
+    view! {
+        div(class="overflow-x-auto") {
+            table(class="w-full text-sm", aria-rowcount=move || (count.get() + 1).to_string()) {
+                caption(class="sr-only") { "Steps in the current filter and order" }
+                thead() {
+                    tr() {
+                        th(scope="col", class=format!("{} text-left", heading)) { "Step" }
+                        th(scope="col", class=format!("{} text-left", heading)) { "Task" }
+                        th(scope="col", class=format!("{} text-left", heading)) { "Scheduled" }
+                        th(scope="col", class=format!("{} text-left", heading)) { "Completed" }
+                        th(scope="col", class=format!("{} text-right", heading)) { "Duration" }
+                        th(scope="col", class=format!("{} text-right", heading)) { "Attempt" }
+                        th(scope="col", class=format!("{} text-right", heading)) { "Failures" }
+                        th(scope="col", class=format!("{} text-left", heading)) { "Unusual duration" }
+                        th(scope="col", class=heading) { span(class="sr-only") { "Actions" } }
+                    }
+                }
+                tbody(r#ref=list_ref, style="overflow-anchor: none;") {
+                    tr(aria-hidden="true", style=above) { td(colspan=COLUMNS.to_string()) }
+                    Keyed(list=mounted, view=move |(position, index, step)| view! {
+                        StepRow(index=index,
+                            position=position,
+                            window_start=window_start,
+                            outlier=outlier(&step),
+                            step=step,
+                            on_show_step=on_show_step)
+                    }, key=|(position, index, _)| (*position, *index))
+                    tr(aria-hidden="true", style=below) { td(colspan=COLUMNS.to_string()) }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::storage::{StoredEntry, TraceStore};
+use crate::{State, Traces};
+use durable_trace::chrono::DateTime;
+use sycamore::prelude::*;
+
+fn size_text(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[component(inline_props)]
+fn StoredTraceRow(entry: StoredEntry) -> View {
+    let state = use_context::<State>();
+    let traces = use_context::<Traces>();
+    let store = use_context::<TraceStore>();
+    let saved_at_text = DateTime::from_timestamp_millis(entry.saved_at as i64)
+        .map(|saved_at| saved_at.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let open_id = entry.durable_execution_id.clone();
+    let open = move |_| {
+        let trace = traces.0.with(|traces| {
+            traces
+                .iter()
+                .find(|trace| trace.durable_execution_id == open_id)
+                .cloned()
+        });
+        state.0.set(trace);
+    };
+    let delete_id = entry.durable_execution_id.clone();
+    let delete = move |_| {
+        traces.remove(&delete_id);
+        store.remove(delete_id.clone());
+    };
+    let row_button = "px-2 py-0.5 text-xs font-medium text-gray-700 border border-gray-300 rounded hover:bg-gray-50";
+    view! {
+        tr(class="border-t") {
+            td(class="py-1 pr-2") { (entry.name) }
+            td(class="py-1 pr-2 text-gray-500") { (entry.durable_execution_id) }
+            td(class="py-1 pr-2 text-right tabular-nums") { (size_text(entry.size)) }
+            td(class="py-1 pr-2 text-gray-500") { (saved_at_text) }
+            td(class="py-1 space-x-1 text-right whitespace-nowrap") {
+                button(on:click=open, class=row_button) { "Open" }
+                button(on:click=delete, class=row_button) { "Delete" }
+            }
+        }
+    }
+}
+
+// Traces kept in the browser, newest first, with how much space each one takes.
+#[component]
+pub(crate) fn StoredTraces() -> View {
+    let store = use_context::<TraceStore>();
+    let total_text = move || {
+        let total = store
+            .entries
+            .with(|entries| entries.iter().map(|entry| entry.size).sum());
+        format!(
+            "{} stored, {}",
+            store.entries.with(Vec::len),
+            size_text(total)
+        )
+    };
+    let newest_first = create_memo(move || {
+        let mut entries = store.entries.get_clone();
+        entries.reverse();
+        entries
+    });
+    let hidden = move || store.entries.with(Vec::is_empty) && store.error.with(Option::is_none);
+    view! {
+        div(class="max-w-xl mx-auto mt-6 w-full border rounded-lg bg-white p-6", hidden=hidden) {
+            div(class="flex items-center justify-between mb-2") {
+                h2(class="text-lg font-medium text-gray-900") { "Saved traces" }
+                span(class="text-sm text-gray-500") { (total_text) }
+            }
+            (store.error.get_clone().map(|error| view! {
+                p(class="mb-2 text-sm text-red-700") { (error) }
+            }))
+            table(class="w-full text-sm text-left text-gray-700") {
+                tbody() {
+                    Keyed(list=newest_first,
+                        view=|entry| view! { StoredTraceRow(entry=entry) },
+                        key=|entry| format!("{}@{}", entry.durable_execution_id, entry.saved_at))
+                }
+            }
+        }
+    }
+}
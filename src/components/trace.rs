@@ -1,10 +1,35 @@
+use crate::browser;
+use crate::components::annotations::Annotations;
+use crate::components::export_menu::ExportMenu;
+use crate::components::markers::{MarkerEditor, MarkerOverlay};
+use crate::components::settings_panel::SettingsPanel;
+use crate::components::shortcuts::ShortcutHelp;
 use crate::components::step_detail::StepDetail;
-use crate::components::util;
-use crate::{DurableTrace, State, StepTrace};
-use serde_with::chrono::{DateTime, Utc};
-use std::clone::Clone;
+use crate::components::step_overview::StepOverview;
+use crate::components::step_table::{StepTable, TABLE_ROW_HEIGHT};
+use crate::settings::Settings;
+use crate::timeline::{StepLayout, StepSort, TimelineView};
+use crate::viewport::Viewport;
+use crate::{Navigation, Page, State, Traces, ViewedTrace};
+use durable_trace::analysis;
+use durable_trace::analysis::outliers::{self, Baseline, Outlier};
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::export::svg::{self, SvgOptions, SvgPalette};
+use durable_trace::{DurableTrace, StepTrace};
+use std::collections::HashMap;
 use sycamore::prelude::*;
 
+// Width of the bar area in exported images before zoom is applied.
+const SVG_CHART_WIDTH: f64 = 800.0;
+// Exported PNGs are rendered at twice the SVG size so they stay sharp on HiDPI screens.
+const PNG_SCALE: f64 = 2.0;
+// Timeline rows have a fixed height so only the ones on screen need mounting: the heading, the
+// bar and the time labels with their spacing, plus the gap to the next row.
+const ROW_HEIGHT: f64 = 116.0;
+const ROW_STRIDE: f64 = ROW_HEIGHT + 24.0;
+// Shorter lists fit on a screen or two and are not worth an overview.
+const OVERVIEW_MIN_STEPS: usize = 50;
+
 #[derive(Props)]
 pub struct TraceProps {
     trace: DurableTrace,
@@ -12,16 +37,14 @@ pub struct TraceProps {
 
 #[component(inline_props)]
 fn Status(status: String, version: u32) -> View {
-    let (border, label) = match status.as_str() {
-        "running" => ("blue", "running"),
-        "failed" => ("red", "failed"),
-        "completed" => ("green", "completed"),
-        _ => ("grey", "unknown"),
-    };
-    let span_class = format!(
-        "border-{}-300 border rounded-full px-4 text-sm text-{}-700 py-0.5",
-        border, border
-    );
+    // Whole class names, so Tailwind generates them and the theme palette applies.
+    let (colours, label) = match status.as_str() {
+        "running" => ("border-blue-300 text-blue-700", "running"),
+        "failed" => ("border-red-300 text-red-700", "failed"),
+        "completed" => ("border-green-300 text-green-700", "completed"),
+        _ => ("border-gray-300 text-gray-700", "unknown"),
+    };
+    let span_class = format!("{} border rounded-full px-4 text-sm py-0.5", colours);
     view! {
         div(class="space-x-2 text-sm") {
             span() {("Status:")}
@@ -32,10 +55,64 @@ fn Status(status: String, version: u32) -> View {
     }
 }
 
+// Switches between the traces loaded from a bundle. Hidden while only one is loaded.
+#[component(inline_props)]
+fn TracePicker(durable_execution_id: String) -> View {
+    let state = use_context::<State>();
+    let traces = use_context::<Traces>();
+    let options = traces.0.with(|traces| {
+        traces
+            .iter()
+            .enumerate()
+            .map(|(index, trace)| {
+                let selected = trace.durable_execution_id == durable_execution_id;
+                let label = format!("{} ({})", trace.name, trace.durable_execution_id);
+                view! { option(value=index.to_string(), selected=selected) { (label) } }
+            })
+            .collect::<Vec<_>>()
+    });
+    let on_change = move |event: web_sys::Event| {
+        if let Ok(index) = browser::event_target_value(&event).parse::<usize>() {
+            if let Some(trace) = traces.0.with(|traces| traces.get(index).cloned()) {
+                state.0.set(Some(trace));
+            }
+        }
+    };
+    if options.len() < 2 {
+        return view! {};
+    }
+    view! {
+        select(on:change=on_change,
+            class="max-w-xs rounded border border-gray-300 bg-white py-2 px-2 text-sm text-gray-700") {
+            (options)
+        }
+    }
+}
+
 #[component(inline_props)]
-fn Header(name: String, payload: String, status: String, duration: String, version: u32) -> View {
+fn Header(
+    name: String,
+    payload: String,
+    status: String,
+    duration: String,
+    version: u32,
+    durable_execution_id: String,
+    scheduled_at: DateTime<Utc>,
+    #[prop(!optional)] completed_at: Option<DateTime<Utc>>,
+) -> View {
+    let settings = use_context::<Settings>();
+    let scheduled_at_text = move || settings.format_time(scheduled_at, scheduled_at);
+    // Relative mode hides when the run happened, so it stays available on hover.
+    let scheduled_at_title = move || settings.format_absolute(scheduled_at);
+    let completed_at_text = move || {
+        completed_at
+            .map(|completed_at| settings.format_time(completed_at, scheduled_at))
+            .unwrap_or_else(|| "Not completed".to_string())
+    };
     let state = use_context::<State>();
     let clear_state = move |_| state.0.set(None);
+    let navigation = use_context::<Navigation>();
+    let many_traces = use_context::<Traces>().0.with(|traces| traces.len() > 1);
     let duration_text_size = if duration.len() > 50 {
         "text-s"
     } else {
@@ -50,6 +127,17 @@ fn Header(name: String, payload: String, status: String, duration: String, versi
                     }
                 }
                 div(class="flex items-center gap-4") {
+                    TracePicker(durable_execution_id=durable_execution_id)
+                    (if many_traces {
+                        view! {
+                            button(on:click=move |_| navigation.0.set(Page::Dashboard),
+                                class="px-4 py-2 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
+                                "Dashboard"
+                            }
+                        }
+                    } else {
+                        view! {}
+                    })
                     button(
                         on:click=clear_state,
                         class="inline-flex items-center gap-2 px-4 py-2 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50") {
@@ -60,6 +148,9 @@ fn Header(name: String, payload: String, status: String, duration: String, versi
                         } "Load JSON"
                     }
 
+                    ExportMenu()
+                    SettingsPanel()
+
                   div() {
                     span(class=format!("font-semibold py-1 px-2.5 border-none rounded bg-blue-100 {} text-blue-800 font-medium",
                             duration_text_size) ) {
@@ -69,6 +160,21 @@ fn Header(name: String, payload: String, status: String, duration: String, versi
                 }
 
             }
+            div(class="mt-1 flex items-center gap-4 text-sm text-gray-500") {
+                p(title=scheduled_at_title) {
+                    "Scheduled " (scheduled_at_text) " · Completed " (completed_at_text)
+                }
+                button(
+                    on:click=move |_| settings.toggle_precise_relative(),
+                    aria-pressed=move || settings.precise_relative().to_string(),
+                    class=move || if settings.precise_relative() {
+                        "px-2 py-0.5 text-xs font-medium rounded border border-blue-600 bg-blue-600 text-white"
+                    } else {
+                        "px-2 py-0.5 text-xs font-medium rounded border border-gray-300 bg-white text-gray-700 hover:bg-gray-50"
+                    }) {
+                    "T+ relative time"
+                }
+            }
         }
 
         Status(status=status, version=version)
@@ -101,69 +207,189 @@ fn Summary(id: String) -> View {
     }
 }
 
+// Colours of the current theme for exported timelines, so they look like the one on screen.
+fn svg_palette() -> SvgPalette {
+    SvgPalette {
+        background: browser::theme_colour("white"),
+        completed: browser::theme_colour("blue-600"),
+        running: browser::theme_colour("blue-300"),
+        track: browser::theme_colour("gray-100"),
+        grid: browser::theme_colour("gray-200"),
+        text: browser::theme_colour("gray-900"),
+        muted: browser::theme_colour("gray-500"),
+    }
+}
+
+/// What a screen reader announces for a step: id, task, timing, failures and anything unusual.
+fn step_description(
+    step: &StepTrace,
+    scheduled_at_text: &str,
+    outlier: Option<&Outlier>,
+) -> String {
+    let mut parts = vec![
+        step.durable_step_id.clone(),
+        match &step.out_task_info {
+            Some(out_task_info) => format!(
+                "task {} then {}",
+                step.in_task_info.task_name, out_task_info.task_name
+            ),
+            None => format!("task {}", step.in_task_info.task_name),
+        },
+        format!("scheduled {}", scheduled_at_text),
+        match analysis::step_duration(step) {
+            Some(duration) => format!("took {}", analysis::fine_duration(duration)),
+            None => "not completed".to_string(),
+        },
+    ];
+    if step.in_task_info.execution_version > 1 {
+        parts.push(format!("attempt {}", step.in_task_info.execution_version));
+    }
+    if step.in_task_info.consecutive_failures > 0 {
+        parts.push(format!(
+            "{} consecutive failures",
+            step.in_task_info.consecutive_failures
+        ));
+    }
+    if let Some(outlier) = outlier {
+        parts.push(format!("unusual duration: {}", outlier.explanation()));
+    }
+    parts.join(", ")
+}
+
 #[component(inline_props)]
 fn StepItem<F>(
+    index: usize,
+    position: usize,
+    count: ReadSignal<usize>,
+    window_start: DateTime<Utc>,
     max_completion: DateTime<Utc>,
-    delta_window: f64,
-    second_rate: f64,
     step: StepTrace,
+    #[prop(!optional)] outlier: Option<Outlier>,
     on_show_step: F,
 ) -> View
 where
     F: Fn(StepTrace) + Copy + 'static,
 {
-    // difference between the latest completed date to the scheduler at of the task
-    let min_delta = max_completion - step.scheduled_at;
-    // the position in % where the step should tart to be rendered
-    let min_position = (delta_window - min_delta.num_seconds() as f64) * 100.0 / delta_window;
-    // if the step is completed, we get the difference from the scheduled_at
-    let max_delta = step
-        .completed_at
-        .map(|completed_at| completed_at - step.scheduled_at);
-
-    // the total width of the step is the duration times how much each second occupies on the screen
-    let task_width = max_delta
-        .map(|max_d| max_d.num_seconds() as f64)
-        .unwrap_or_else(|| delta_window * 0.02) // defaults to 20%
-        * second_rate;
-
-    let duration_text = match step.completed_at {
-        Some(completed_at) => format!(
-            "{} seconds",
-            (completed_at - step.scheduled_at).num_seconds()
-        ),
-        None => "Not completed".to_string(),
-    };
-    let start_at_text = step.scheduled_at.to_rfc3339();
-    let end_at_text = step
-        .completed_at
-        .map(|completed_at| completed_at.to_rfc3339())
-        .unwrap_or_else(|| "-".to_string());
+    // where the bar starts and how wide it is, in % of the whole execution window
+    let bar_position = analysis::bar_position(&step, window_start, max_completion);
 
+    // Same format as the table layout.
+    let duration_text = analysis::step_duration(&step)
+        .map(analysis::fine_duration)
+        .unwrap_or_else(|| "Not completed".to_string());
+    let settings = use_context::<Settings>();
+    let (scheduled_at, completed_at) = (step.scheduled_at, step.completed_at);
+    let start_at_text = move || settings.format_time(scheduled_at, window_start);
+    let end_at_text = move || {
+        completed_at
+            .map(|completed_at| settings.format_time(completed_at, window_start))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let viewed = use_context::<ViewedTrace>();
+    let note_step = step.clone();
+    let notes = create_memo(move || {
+        viewed.trace.with(|trace| {
+            trace
+                .annotations
+                .iter()
+                .filter(|annotation| annotation.is_on_step(&note_step))
+                .map(|annotation| annotation.text.clone())
+                .collect::<Vec<_>>()
+        })
+    });
     let step_id = step.durable_step_id.clone();
+    let label_step = step.clone();
+    let label_outlier = outlier.clone();
+    let label = move || {
+        let description = step_description(
+            &label_step,
+            &settings.format_time(scheduled_at, window_start),
+            label_outlier.as_ref(),
+        );
+        match notes.with(Vec::len) {
+            0 => format!("View details for {}", description),
+            count => format!("View details for {}, {} notes", description, count),
+        }
+    };
+    let timeline = use_context::<TimelineView>();
     let on_show = move |_| {
+        timeline.selected.set(Some(index));
         on_show_step(step.clone());
     };
+    // The row picked with the arrow keys is outlined; `Steps` keeps it on screen.
+    let is_selected = create_selector(move || timeline.selected.get() == Some(index));
+    let row_class = move || {
+        if is_selected.get() {
+            "absolute inset-x-0 space-y-6 rounded-lg ring-2 ring-indigo-500 ring-offset-4"
+        } else {
+            "absolute inset-x-0 space-y-6 rounded-lg"
+        }
+    };
+    let row_style = format!(
+        "top: {}px; height: {}px;",
+        position as f64 * ROW_STRIDE,
+        ROW_HEIGHT
+    );
+    // Durations far from earlier runs of the same step get a badge and an outlined bar.
+    let (outlier_badge, bar_outline) = match outlier {
+        Some(outlier) => {
+            let label = if outlier.is_slow() { "slow" } else { "fast" };
+            // Without spread in earlier runs there is no finite number of deviations to show.
+            let badge_text = if outlier.z_score.is_finite() {
+                format!("{} ({:+.1}σ)", label, outlier.z_score)
+            } else {
+                label.to_string()
+            };
+            let badge = view! {
+                span(class="ml-2 shrink-0 px-2 rounded-full text-xs font-medium bg-amber-100 text-amber-800",
+                    title=outlier.explanation()) {
+                    (badge_text)
+                }
+            };
+            (badge, " ring-2 ring-amber-500")
+        }
+        None => (view! {}, ""),
+    };
     view! {
-        div(class="relative") {
-            div(class="flex items-center mb-2") {
-             span(class="text-sm font-medium text-gray-900") { (step_id) }
-             span(class="ml-2 text-xs text-gray-500"){ (duration_text) }
+        // Only some rows are mounted, so each says where it sits in the whole list.
+        div(class=row_class, style=row_style, role="listitem",
+            aria-posinset=(position + 1).to_string(), aria-setsize=move || count.get().to_string(),
+            aria-current=move || if is_selected.get() { "true" } else { "false" }) {
+            // The bar's label says all of this, so screen readers skip the visual heading.
+            div(class="relative", aria-hidden="true") {
+                div(class="flex items-center mb-2") {
+                 span(class="min-w-0 truncate text-sm font-medium text-gray-900") { (step_id) }
+                 span(class="ml-2 shrink-0 text-xs text-gray-500"){ (duration_text) }
+                 (outlier_badge)
+                }
             }
-        }
-        div(class="h-8 relative bg-gray-100 rounded-lg overflow-visible group") {
-         button(
-                on:click=on_show,
-                class="absolute h-full transition-all bg-blue-600 hover:bg-blue-600 hover:bg-teal-600 duration-300 ease-in-out transform hover:scale-105",
-                style=format!("left: {}%; width: {}%;",  min_position, task_width)) {
+            div(class="h-8 relative bg-gray-100 rounded-lg overflow-visible group") {
+             button(
+                    on:click=on_show,
+                    class=format!("absolute h-full transition-all bg-blue-600 hover:bg-blue-600 hover:bg-teal-600 duration-300 ease-in-out transform hover:scale-105{}", bar_outline),
+                    style=format!("left: {}%; width: {}%;", bar_position.left, bar_position.width)) {
 
-                span(class="sr-only") { "View details for assign-card-trx"}
-            }
+                    span(class="sr-only") { (label) }
+                }
+                // Pinned at the start of the bar, with the notes in the tooltip.
+                (if notes.with(Vec::is_empty) {
+                    view! {}
+                } else {
+                    view! {
+                        span(class="absolute -top-2 z-10 px-1 rounded-full text-xs font-medium bg-amber-400 text-amber-950 pointer-events-auto",
+                            style=format!("left: {}%;", bar_position.left),
+                            title=notes.with(|notes| notes.join("\n"))) {
+                            (notes.with(Vec::len))
+                        }
+                    }
+                })
 
-        }
-        div(class="flex justify-between mt-1 text-xs text-gray-500"){
-            span() { (start_at_text) }
-            span() { (end_at_text) }
+            }
+            div(class="flex justify-between mt-1 text-xs text-gray-500"){
+                span() { (start_at_text) }
+                span() { (end_at_text) }
+            }
         }
     }
 }
@@ -171,59 +397,286 @@ where
 #[derive(Clone, Debug)]
 enum StepDetailEnum {
     NotSet,
-    Loaded(StepTrace),
+    Loaded(Box<StepTrace>),
 }
 
 #[component(inline_props)]
 pub fn Steps(
+    name: String,
     steps: Vec<StepTrace>,
     durable_scheduled_at: DateTime<Utc>,
     durable_completed_at: Option<DateTime<Utc>>,
+    baselines: HashMap<String, Baseline>,
 ) -> View {
+    let timeline = use_context::<TimelineView>();
     let max_completion =
-        util::find_max_completion(&steps, durable_scheduled_at, durable_completed_at);
-    let min_completion = durable_scheduled_at;
-    let delta_window = (max_completion - min_completion).num_seconds() as f64;
-    let second_rate = 1.0 * 100.0 / delta_window;
+        analysis::find_max_completion(&steps, durable_scheduled_at, durable_completed_at);
+    let window_start = durable_scheduled_at;
+    // Rows hold indexes into `steps`; only the mounted ones clone their step.
+    let steps = *create_signal(steps);
+    let visible_steps = create_memo(move || steps.with(|steps| timeline.visible_steps(steps)));
+    let count = create_memo(move || visible_steps.with(Vec::len));
+    let show_overview = create_selector(move || count.get() > OVERVIEW_MIN_STEPS);
+    let baselines = create_signal(baselines);
+    let outlier =
+        move |step: &StepTrace| baselines.with(|baselines| outliers::assess(baselines, step));
     let step_detail = create_signal(StepDetailEnum::NotSet);
     let on_hide_step = move || step_detail.set(StepDetailEnum::NotSet);
-    let on_show_step = move |step| step_detail.set(StepDetailEnum::Loaded(step));
+    let on_show_step = move |step| step_detail.set(StepDetailEnum::Loaded(Box::new(step)));
+
+    // Selections are trace indexes, so one left over from another trace means nothing here.
+    timeline.selected.set(None);
+    let show_shortcuts = create_signal(false);
+    let filter_ref = create_node_ref();
+    let move_selection = move |forward: bool| {
+        let next = visible_steps.with(|visible| {
+            let position = timeline
+                .selected
+                .get_untracked()
+                .and_then(|selected| visible.iter().position(|index| *index == selected));
+            let next = match (position, forward) {
+                (None, _) => 0,
+                (Some(position), true) => (position + 1).min(visible.len().saturating_sub(1)),
+                (Some(position), false) => position.saturating_sub(1),
+            };
+            visible.get(next).copied()
+        });
+        if next.is_some() {
+            timeline.selected.set(next);
+        }
+    };
+    let open_selected = move || {
+        let step = timeline
+            .selected
+            .get_untracked()
+            .filter(|selected| visible_steps.with(|visible| visible.contains(selected)))
+            .and_then(|selected| steps.with(|steps| steps.get(selected).cloned()));
+        if let Some(step) = step {
+            on_show_step(step);
+        }
+    };
+    browser::on_window_keydown(move |event| {
+        if event.ctrl_key() || event.meta_key() || event.alt_key() {
+            return;
+        }
+        let key = event.key();
+        // Typing in a field only gives up focus on Escape; Enter on a button presses it.
+        let target = browser::event_target_tag(&event);
+        if matches!(target.as_str(), "INPUT" | "TEXTAREA" | "SELECT") {
+            if key == "Escape" {
+                browser::blur_target(&event);
+            }
+            return;
+        }
+        match key.as_str() {
+            "ArrowDown" => move_selection(true),
+            "ArrowUp" => move_selection(false),
+            "Enter" if !matches!(target.as_str(), "BUTTON" | "A") => open_selected(),
+            "Escape" if show_shortcuts.get_untracked() => show_shortcuts.set(false),
+            "Escape" => on_hide_step(),
+            "/" => {
+                if let Some(filter) = filter_ref.try_get() {
+                    browser::focus(&filter);
+                }
+            }
+            "+" | "=" => timeline.zoom_in(),
+            "-" => timeline.zoom_out(),
+            "?" => show_shortcuts.set(!show_shortcuts.get_untracked()),
+            _ => return,
+        }
+        event.prevent_default();
+    });
+
+    let settings = use_context::<Settings>();
+    let name = create_signal(name);
+    let svg_options = move || SvgOptions {
+        title: name.get_clone(),
+        chart_width: SVG_CHART_WIDTH * timeline.zoom.get(),
+        time_base: settings.time_base(),
+        palette: svg_palette(),
+    };
+    let render_svg = move |options: &SvgOptions| {
+        let steps = visible_steps.with(|visible| {
+            steps.with(|steps| {
+                visible
+                    .iter()
+                    .map(|index| steps[*index].clone())
+                    .collect::<Vec<_>>()
+            })
+        });
+        (
+            svg::render(&steps, window_start, max_completion, options),
+            svg::size(steps.len(), options),
+        )
+    };
+    let export_svg = move |_| {
+        let options = svg_options();
+        let (document, _) = render_svg(&options);
+        browser::download_text(
+            &format!("{}-timeline.svg", options.title),
+            "image/svg+xml",
+            &document,
+        );
+    };
+    let export_png = move |_| {
+        let options = svg_options();
+        let (document, (width, height)) = render_svg(&options);
+        browser::download_svg_as_png(
+            &format!("{}-timeline.png", options.title),
+            &document,
+            width,
+            height,
+            PNG_SCALE,
+        );
+    };
+    let on_sort_change = move |event: web_sys::Event| {
+        let value = browser::event_target_value(&event);
+        timeline.sort.set(StepSort::from_key(&value));
+    };
+    let sort_options = StepSort::ALL
+        .into_iter()
+        .map(|sort| {
+            view! {
+                option(value=sort.key(), selected=timeline.sort.get_untracked() == sort) { (sort.label()) }
+            }
+        })
+        .collect::<Vec<_>>();
+    let zoom_text = move || format!("{:.0}%", timeline.zoom.get() * 100.0);
+    let layout_buttons = StepLayout::ALL
+        .into_iter()
+        .map(|layout| {
+            let class = move || {
+                if timeline.layout.get() == layout {
+                    "px-3 py-1 text-sm font-medium border border-indigo-500 bg-indigo-50 text-indigo-700 first:rounded-l-md last:rounded-r-md"
+                } else {
+                    "px-3 py-1 text-sm font-medium border border-gray-300 bg-white text-gray-700 hover:bg-gray-50 first:rounded-l-md last:rounded-r-md"
+                }
+            };
+            view! {
+                button(on:click=move |_| timeline.layout.set(layout), class=class,
+                    aria-pressed=move || (timeline.layout.get() == layout).to_string()) {
+                    (layout.label())
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let toolbar_button = "px-3 py-1 text-sm font-medium text-gray-700 bg-white border border-gray-300 rounded-md hover:bg-gray-50";
+
     view! {
-        div(class="space-y-6") {
-            Keyed(list=steps,
-            view=move |step| view! {
-                    StepItem(max_completion=max_completion,
-                        delta_window=delta_window,
-                        second_rate=second_rate, step=step,
-                        on_show_step=on_show_step)
-            },
-            key=|step| step.durable_step_id.clone())
+        div(class="flex flex-wrap items-center justify-between gap-4 mb-6") {
+            input(r#ref=filter_ref, bind:value=timeline.filter, r#type="search", aria-label="Filter steps", placeholder="Filter by step id or task name (/)",
+                class="w-72 rounded border border-gray-300 bg-white py-1 px-3 text-sm text-gray-700 outline-none focus:border-indigo-500 focus:ring-2 focus:ring-indigo-200")
+            div(class="flex items-center gap-2") {
+                select(on:change=on_sort_change, aria-label="Sort steps", class="rounded border border-gray-300 bg-white py-1 px-2 text-sm text-gray-700") {
+                    (sort_options)
+                }
+                div(role="group", aria-label="Layout", class="flex") {
+                    (layout_buttons)
+                }
+                button(on:click=move |_| timeline.zoom_out(), class=toolbar_button, aria-label="Zoom out") { "-" }
+                span(class="w-12 text-center text-sm text-gray-700", aria-live="polite") { (zoom_text) }
+                button(on:click=move |_| timeline.zoom_in(), class=toolbar_button, aria-label="Zoom in") { "+" }
+                button(on:click=export_svg, class=toolbar_button) { "Export SVG" }
+                button(on:click=export_png, class=toolbar_button) { "Export PNG" }
+                button(on:click=move |_| show_shortcuts.set(true), class=toolbar_button, title="Keyboard shortcuts", aria-label="Keyboard shortcuts") { "?" }
+            }
         }
+        (match timeline.layout.get() {
+            StepLayout::Timeline => {
+                let viewport = Viewport::new(ROW_STRIDE);
+                viewport.follow(visible_steps, timeline.selected);
+                let mounted = viewport.mounted(steps, visible_steps);
+                let list_style = move || {
+                    format!(
+                        "height: {}px; overflow-anchor: none;",
+                        count.get() as f64 * ROW_STRIDE
+                    )
+                };
+                let list_ref = viewport.list_ref();
+                view! {
+                    div(class="overflow-x-auto") {
+                        div(style=move || format!("width: {}%;", timeline.zoom.get() * 100.0)) {
+                            (if show_overview.get() {
+                                view! {
+                                    StepOverview(steps=steps, visible=visible_steps, window_start=window_start,
+                                        window_end=max_completion, outlier=outlier, viewport=viewport)
+                                }
+                            } else {
+                                view! {}
+                            })
+                            div(class="relative") {
+                                MarkerOverlay(window_start=window_start, window_end=max_completion)
+                                div(r#ref=list_ref, class="relative", style=list_style,
+                                    role="list", aria-label="Steps") {
+                                    // Keyed by position too, so rows that move when filtering are placed again.
+                                    Keyed(list=mounted,
+                                    view=move |(position, index, step)| view! {
+                                            StepItem(index=index,
+                                                position=position,
+                                                count=count,
+                                                window_start=window_start,
+                                                max_completion=max_completion,
+                                                outlier=outlier(&step),
+                                                step=step,
+                                                on_show_step=on_show_step)
+                                    },
+                                    key=|(position, index, _)| (*position, *index))
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            StepLayout::Table => {
+                let viewport = Viewport::new(TABLE_ROW_HEIGHT);
+                viewport.follow(visible_steps, timeline.selected);
+                view! {
+                    (if show_overview.get() {
+                        view! {
+                            StepOverview(steps=steps, visible=visible_steps, window_start=window_start,
+                                window_end=max_completion, outlier=outlier, viewport=viewport)
+                        }
+                    } else {
+                        view! {}
+                    })
+                    StepTable(steps=steps, visible=visible_steps, window_start=window_start, outlier=outlier,
+                        on_show_step=on_show_step, viewport=viewport)
+                }
+            }
+        })
         (match step_detail.get_clone() {
-            StepDetailEnum::Loaded(step_trace) => view! { StepDetail(step_trace=step_trace, on_hide_step=on_hide_step) },
+            StepDetailEnum::Loaded(step_trace) => view! {
+                StepDetail(outlier=outlier(&step_trace), step_trace=*step_trace, on_hide_step=on_hide_step)
+            },
             StepDetailEnum::NotSet => view! {},
         })
+        (if show_shortcuts.get() {
+            view! { ShortcutHelp(on_close=move || show_shortcuts.set(false)) }
+        } else {
+            view! {}
+        })
     }
 }
 
 #[component]
 pub fn Trace(props: TraceProps) -> View {
-    let delta_opt = props
-        .trace
-        .completed_at
-        .map(|completed_at| completed_at.signed_duration_since(props.trace.scheduled_at));
-
-    let duration_string = match delta_opt {
-        Some(delta) => util::get_duration_string(delta),
+    let duration_string = match analysis::trace_duration(&props.trace) {
+        Some(delta) => analysis::get_duration_string(delta),
         None => "-".to_string(),
     };
 
+    provide_context(ViewedTrace::new(props.trace.clone()));
+    let header_execution_id = props.trace.durable_execution_id.clone();
+    let steps_name = props.trace.name.clone();
+    let baselines = use_context::<Traces>()
+        .0
+        .with(|traces| outliers::baselines(&props.trace, traces));
     let step_view = match props.trace.completed_at {
         Some(completed_at) => view! {
-            Steps(steps=props.trace.steps, durable_scheduled_at=props.trace.scheduled_at, durable_completed_at=completed_at)
+            Steps(name=steps_name, steps=props.trace.steps, durable_scheduled_at=props.trace.scheduled_at, durable_completed_at=completed_at, baselines=baselines)
         },
         None => view! {
-            Steps(steps=props.trace.steps, durable_scheduled_at=props.trace.scheduled_at)
+            Steps(name=steps_name, steps=props.trace.steps, durable_scheduled_at=props.trace.scheduled_at, baselines=baselines)
         },
     };
 
@@ -233,8 +686,19 @@ pub fn Trace(props: TraceProps) -> View {
                 payload=props.trace.payload.unwrap_or_else(|| "No Payload".to_string()),
                 status=props.trace.status,
                 duration=duration_string,
-                version=props.trace.version)
+                version=props.trace.version,
+                durable_execution_id=header_execution_id,
+                scheduled_at=props.trace.scheduled_at,
+                completed_at=props.trace.completed_at)
             Summary(id=props.trace.durable_execution_id.clone())
+            div(class="mb-8") {
+                h3(class="text-sm font-medium text-gray-700 mb-2") { "Notes" }
+                Annotations(durable_step_id=None)
+            }
+            div(class="mb-8") {
+                h3(class="text-sm font-medium text-gray-700 mb-2") { "Markers" }
+                MarkerEditor()
+            }
             (step_view)
         }
     }
@@ -1,19 +1,40 @@
-use crate::{DurableTrace, State};
+use crate::browser;
+use crate::components::stored_traces::StoredTraces;
+use crate::storage::TraceStore;
+use crate::{Navigation, Page, State, Traces};
+use durable_trace::import::{bundle, TraceFormat};
+use durable_trace::schema;
+use durable_trace::DurableTrace;
 
-use serde_json;
 use sycamore::prelude::*;
 use sycamore::rt::console_error;
 
 #[component(inline_props)]
-fn TraceInputErrorModal<F>(on_hider_error: F, error_msg: ReadSignal<String>) -> View
+fn TraceInputErrorModal<F>(
+    on_hider_error: F,
+    title: ReadSignal<&'static str>,
+    error_msg: ReadSignal<String>,
+) -> View
 where
     F: Fn() + Copy + 'static,
 {
+    let dialog_ref = create_node_ref();
+    browser::focus_while_mounted(dialog_ref);
+    let on_keydown = move |event: web_sys::KeyboardEvent| {
+        if event.key() == "Escape" {
+            on_hider_error();
+        } else {
+            browser::contain_tab(&event);
+        }
+    };
     view! {
-        div(class="fixed z-50 inset-0 bg-gray-900 bg-opacity-60 overflow-y-auto h-full w-full px-4") {
-            div(class="relative top-40 mx-auto shadow-xl rounded-md bg-white max-w-md") {
+        div(class="fixed z-50 inset-0 bg-black/60 overflow-y-auto h-full w-full px-4") {
+            div(r#ref=dialog_ref, role="alertdialog", aria-modal="true",
+                aria-labelledby="trace-input-error-title", aria-describedby="trace-input-error-message",
+                on:keydown=on_keydown,
+                class="relative top-40 mx-auto shadow-xl rounded-md bg-white max-w-md") {
                 div(class="flex justify-end p-2") {
-                    button(on:click=move |_| on_hider_error(),
+                    button(on:click=move |_| on_hider_error(), aria-label="Close",
                         class="text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm p-1.5 ml-auto inline-flex items-center") {
                             svg(class="w-5 h-5", fill="currentColor", viewBox="0 0 20 20", xmlns="http://www.w3.org/2000/svg") {
                                 path(fill-rule="evenodd",
@@ -28,10 +49,8 @@ where
                             path(stroke-linecap="round", stroke-linejoin="round", stroke-width="2",
                                 d="M12 8v4m0 4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z")
                         }
-                    h3(class="text-xl font-normal text-gray-500 mt-5 mb-6") {
-                        "Invalid Json. Please paste a valid durable trace json:"
-                    }
-                    p(class="text-s") { (error_msg) }
+                    h3(id="trace-input-error-title", class="text-xl font-normal text-gray-500 mt-5 mb-6") { (title) }
+                    pre(id="trace-input-error-message", class="text-s text-left whitespace-pre-wrap") { (error_msg) }
                 }
             }
         }
@@ -39,29 +58,80 @@ where
 }
 
 #[component(inline_props)]
-fn TraceInputText<F>(on_error: F, err_message: Signal<String>) -> View
+fn TraceInputText<F>(
+    on_error: F,
+    err_message: Signal<String>,
+    partially_loaded: Signal<Option<DurableTrace>>,
+) -> View
 where
     F: Fn() + Copy + 'static,
 {
     let state = use_context::<State>();
+    let traces = use_context::<Traces>();
+    let store = use_context::<TraceStore>();
     let payload = create_signal(String::new());
-    let parse_json =
-        move |_| match serde_json::from_str::<DurableTrace>(payload.get_clone().as_str()) {
-            Ok(p) => {
-                state.0.set(Some(p));
-            }
-            Err(e) => {
-                console_error!("{}", e);
+    // `None` lets the importer detect the format from the document.
+    let format = create_signal(None::<TraceFormat>);
+    let on_format_change = move |event: web_sys::Event| {
+        format.set(TraceFormat::from_key(&browser::event_target_value(&event)));
+    };
+    let format_options = TraceFormat::ALL
+        .into_iter()
+        .map(|format| view! { option(value=format.key()) { (format.label()) } })
+        .collect::<Vec<_>>();
+    // Bundles keep every trace that imported and list the entries that did not.
+    let parse_json = move |_| {
+        let bundle = bundle::import(payload.get_clone().as_str(), format.get());
+        let first = bundle.traces.first().cloned();
+        let message = match (bundle.traces.len(), bundle.failures.as_slice()) {
+            (1.., []) => None,
+            (0, []) => Some("No traces found.".to_string()),
+            (0, [failure]) => Some(failure.error.to_string()),
+            (loaded, failures) => Some(
+                std::iter::once(format!(
+                    "Loaded {} of {} traces.",
+                    loaded,
+                    loaded + failures.len()
+                ))
+                .chain(failures.iter().map(ToString::to_string))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ),
+        };
+        store.save(bundle.traces.clone());
+        traces.add(bundle.traces);
+        match message {
+            None => state.0.set(first),
+            Some(message) => {
+                console_error!("{}", message);
+                partially_loaded.set(first);
+                err_message.set(message);
                 on_error();
-                err_message.set(e.to_string());
             }
-        };
+        }
+    };
+    let navigation = use_context::<Navigation>();
+    let loaded_traces = traces.0.with(Vec::len);
+    let schema_href = format!(
+        "data:application/json;charset=utf-8,{}",
+        js_sys::encode_uri_component(&schema::trace_schema_text())
+    );
     view! {
         div(class="max-w-xl mx-auto mt-16 flex w-full flex-col border rounded-lg bg-white p-8") {
             h2(class="title-font mb-1 text-lg font-medium text-gray-900") { "Durable Trace" }
-            p(class="mb-5 leading-relaxed text-gray-600") { "Please paste the durable trace json" }
+            p(class="mb-5 leading-relaxed text-gray-600") {
+                "Please paste the durable trace json, a JSON array of traces or one trace per line"
+            }
+            div(class="mb-4") {
+                label(r#for="format", class="text-sm leading-7 text-gray-600") {"Format" }
+                select(on:change=on_format_change, id="format", name="format",
+                    class="w-full rounded border border-gray-300 bg-white py-1 px-3 text-base leading-6 text-gray-700 outline-none focus:border-indigo-500 focus:ring-2 focus:ring-indigo-200") {
+                    option(value="auto") { "Auto-detect" }
+                    (format_options)
+                }
+            }
             div(class="mb-4") {
-                label(class="text-sm leading-7 text-gray-600") {"Payload" }
+                label(r#for="payload", class="text-sm leading-7 text-gray-600") {"Payload" }
                 textarea(bind:value=payload,
                     id="payload", name="payload", class="h-32 w-full resize-none rounded border border-gray-300 bg-white py-1 px-3 text-base leading-6 text-gray-700 outline-none transition-colors duration-200 ease-in-out focus:border-indigo-500 focus:ring-2 focus:ring-indigo-200")
             }
@@ -75,22 +145,60 @@ where
                     "Example JSON"
                 } " to get started."
             }
+            (if loaded_traces > 0 {
+                view! {
+                    div(class="text-m text-gray-900 d-flex justify-content-end align-items-center") {
+                        (loaded_traces) " traces are loaded. Open the "
+                        button(on:click=move |_| navigation.0.set(Page::Dashboard),
+                            class="underline text-blue-600 hover:text-blue-800") {
+                            "Dashboard"
+                        } "."
+                    }
+                }
+            } else {
+                view! {}
+            })
+            div(class="text-m text-gray-900 d-flex justify-content-end align-items-center") {
+                "Producers can check their output against the "
+                a(class="underline text-blue-600 hover:text-blue-800 visited:text-purple-600",
+                href=schema_href, download=schema::SCHEMA_FILE_NAME) {
+                    "JSON Schema"
+                } "."
+            }
         }
     }
 }
 
 #[component]
 pub fn TraceInput() -> View {
+    let state = use_context::<State>();
     let show_error = create_signal(false);
     let set_show_error = move || show_error.set(true);
-    let set_hide_error = move || show_error.set(false);
+    let partially_loaded = create_signal(None::<DurableTrace>);
+    // After a partial bundle load, dismissing the failures opens the traces that did load.
+    let set_hide_error = move || {
+        show_error.set(false);
+        if let Some(trace) = partially_loaded.take() {
+            state.0.set(Some(trace));
+        }
+    };
     let err_msg = create_signal(String::new());
     let err_msg_read = create_memo(move || err_msg.get_clone());
+    let title = create_memo(move || {
+        if partially_loaded.with(Option::is_some) {
+            "Some traces could not be imported:"
+        } else {
+            "Invalid Json. Please paste a valid durable trace json:"
+        }
+    });
+    // The form stays mounted under the modal so focus can go back to it once the modal closes.
     view! {
+        TraceInputText(on_error=set_show_error, err_message=err_msg, partially_loaded=partially_loaded)
+        StoredTraces()
         (if show_error.get() {
-            view! {TraceInputErrorModal(on_hider_error=set_hide_error,error_msg=err_msg_read)}
+            view! {TraceInputErrorModal(on_hider_error=set_hide_error, title=title, error_msg=err_msg_read)}
         } else {
-            view! {TraceInputText(on_error=set_show_error, err_message=err_msg)}
+            view! {}
         })
     }
 }
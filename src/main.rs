@@ -1,123 +1,220 @@
+use crate::components::command_palette::CommandPalette;
+use crate::components::dashboard::Dashboard;
 use crate::components::trace_input::TraceInput;
-use serde_with::chrono::{DateTime, Utc};
+use durable_trace::import::{bundle, TraceFormat};
+use durable_trace::{parse_trace, DurableTrace};
 
 use crate::components::trace::Trace;
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use crate::settings::Settings;
+use crate::storage::TraceStore;
+use crate::timeline::TimelineView;
 use sycamore::prelude::*;
+use sycamore::rt::console_error;
+use wasm_bindgen_futures::spawn_local;
 
+mod browser;
 mod components;
+mod settings;
+mod storage;
+mod timeline;
+mod viewport;
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
-#[serde_as]
-struct TaskInfo {
-    id: String,
-    task_name: String,
-    execution_time: DateTime<Utc>,
-    consecutive_failures: u32,
-    execution_version: u32,
-    last_failure: Option<DateTime<Utc>>,
-    last_success: Option<DateTime<Utc>>,
-}
+#[derive(Debug, Clone, Copy)]
+struct State(Signal<Option<DurableTrace>>);
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
-#[serde_as]
-struct StepTrace {
-    durable_step_id: String,
-    result: Option<String>,
-    scheduled_at: DateTime<Utc>,
-    completed_at: Option<DateTime<Utc>>,
-    in_task_info: TaskInfo,
-    out_task_info: Option<TaskInfo>,
-}
+// Every trace loaded so far, oldest first. `State` holds the one being viewed.
+#[derive(Debug, Clone, Copy)]
+struct Traces(Signal<Vec<DurableTrace>>);
+
+impl Traces {
+    // Loading an execution again replaces the copy loaded before.
+    fn add(&self, traces: Vec<DurableTrace>) {
+        self.0.update(|loaded| {
+            for trace in traces {
+                loaded.retain(|other| other.durable_execution_id != trace.durable_execution_id);
+                loaded.push(trace);
+            }
+        });
+    }
+
+    fn remove(&self, durable_execution_id: &str) {
+        self.0.update(|loaded| {
+            loaded.retain(|trace| trace.durable_execution_id != durable_execution_id)
+        });
+    }
 
-impl StepTrace {
-    fn in_task_text(&self) -> String {
-        serde_json::to_string_pretty(&self.in_task_info).unwrap_or_default()
+    // Unlike `add`, keeps the trace where it is in the list.
+    fn replace(&self, trace: DurableTrace) {
+        self.0.update(|loaded| {
+            match loaded
+                .iter_mut()
+                .find(|other| other.durable_execution_id == trace.durable_execution_id)
+            {
+                Some(other) => *other = trace,
+                None => loaded.push(trace),
+            }
+        });
     }
+}
 
-    fn out_task_text(&self) -> String {
-        self.out_task_info
-            .as_ref()
-            .map(|info| serde_json::to_string_pretty(&info).unwrap_or_default())
-            .unwrap_or_default()
+// The trace on screen while it is being edited, e.g. annotated. Edits are saved without
+// re-rendering the whole trace view.
+#[derive(Debug, Clone, Copy)]
+struct ViewedTrace {
+    trace: Signal<DurableTrace>,
+    state: State,
+    traces: Traces,
+    store: TraceStore,
+}
+
+impl ViewedTrace {
+    fn new(trace: DurableTrace) -> Self {
+        ViewedTrace {
+            trace: create_signal(trace),
+            state: use_context::<State>(),
+            traces: use_context::<Traces>(),
+            store: use_context::<TraceStore>(),
+        }
     }
 
-    fn completed_at_text(&self) -> String {
-        self.completed_at
-            .as_ref()
-            .map(|completed_at| completed_at.to_rfc3339())
-            .unwrap_or("-".to_string())
+    fn edit(self, f: impl FnOnce(&mut DurableTrace)) {
+        self.trace.update(f);
+        let trace = self.trace.get_clone_untracked();
+        self.state.0.set_silent(Some(trace.clone()));
+        self.traces.replace(trace.clone());
+        self.store.save(vec![trace]);
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-#[serde_as]
-struct DurableTrace {
-    name: String,
-    durable_execution_id: String,
-    scheduled_at: DateTime<Utc>,
-    completed_at: Option<DateTime<Utc>>,
-    payload: Option<String>,
-    result: Option<String>,
-    is_error: Option<bool>,
-    status: String,
-    failure_reason: Option<String>,
-    failure_source: Option<String>,
-    version: u32,
-    steps: Vec<StepTrace>,
+// The screen shown next to the trace input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Trace,
+    Dashboard,
 }
 
 #[derive(Debug, Clone, Copy)]
-struct State(Signal<Option<DurableTrace>>);
+struct Navigation(Signal<Page>);
+
+// Execution id of the trace being viewed, so a reload opens it again.
+const CURRENT_TRACE_KEY: &str = "current-trace";
 
 #[component]
 fn App() -> View {
     let state = use_context::<State>();
-    let local_store = window().local_storage().unwrap().expect("No local storage");
+    let traces = use_context::<Traces>();
+    let store = use_context::<TraceStore>();
+    let navigation = use_context::<Navigation>();
+    let local_store = window().local_storage().ok().flatten();
 
-    let saved_trace: Option<DurableTrace> = if let Ok(Some(trace)) = local_store.get_item("trace") {
-        match serde_json::from_str::<DurableTrace>(&trace) {
-            Ok(trace) => Some(trace),
-            _ => None,
+    let (legacy_current, legacy_traces) = local_store
+        .as_ref()
+        .map(read_legacy_traces)
+        .unwrap_or_default();
+    let current_id = local_store
+        .as_ref()
+        .and_then(|local_store| local_store.get_item(CURRENT_TRACE_KEY).ok().flatten())
+        .or(legacy_current);
+
+    let legacy_store = local_store.clone();
+    spawn_local(async move {
+        match store.open().await {
+            Ok(stored) => traces.add(stored),
+            Err(e) => {
+                console_error!("{}", e);
+                store.error.set(Some(e.to_string()));
+            }
         }
-    } else {
-        Default::default()
-    };
+        if !legacy_traces.is_empty() {
+            // The old copies are only dropped once IndexedDB holds the traces, so a failed or
+            // unavailable database loses nothing.
+            if store.persist(&legacy_traces).await.is_ok() {
+                if let Some(legacy_store) = &legacy_store {
+                    remove_legacy_traces(legacy_store);
+                }
+            }
+            traces.add(legacy_traces);
+        }
+        let current = traces.0.with_untracked(|traces| {
+            traces
+                .iter()
+                .find(|trace| Some(&trace.durable_execution_id) == current_id.as_ref())
+                .cloned()
+        });
+        state.0.set(current);
+        store.ready.set(true);
+    });
 
-    state.0.set(saved_trace);
     create_effect(move || {
         state.0.with(|trace| {
-            if let Some(trace) = trace {
-                local_store
-                    .set_item("trace", &serde_json::to_string(trace).unwrap())
-                    .unwrap();
+            if let (Some(trace), Some(local_store)) = (trace, &local_store) {
+                let _ = local_store.set_item(CURRENT_TRACE_KEY, &trace.durable_execution_id);
             }
         })
     });
 
     view! {
-        (match state.0.get_clone()  {
-            Some(trace) => view! {
-                // Payload is there. Let's render the trace.
-                Trace(trace=trace)
-            },
-            None => {
-                view! {
-                    // We don't have a payload. Ask the user.
-                    TraceInput()
+        CommandPalette()
+        (if !store.ready.get() {
+            view! {
+                p(class="mt-16 text-center text-gray-500") { "Loading saved traces..." }
+            }
+        } else {
+            match (navigation.0.get(), state.0.get_clone()) {
+                (Page::Dashboard, _) => view! {
+                    Dashboard()
+                },
+                (Page::Trace, Some(trace)) => view! {
+                    // Payload is there. Let's render the trace.
+                    Trace(trace=trace)
+                },
+                (Page::Trace, None) => {
+                    view! {
+                        // We don't have a payload. Ask the user.
+                        TraceInput()
+                    }
                 }
-            }})
+            }
+        })
+    }
+}
+
+// Earlier versions kept the viewed trace under `trace` and every loaded trace under `traces` in
+// localStorage. They move to IndexedDB on the first start; returns the viewed execution id too.
+fn read_legacy_traces(local_store: &web_sys::Storage) -> (Option<String>, Vec<DurableTrace>) {
+    let mut legacy = Vec::new();
+    if let Ok(Some(saved_traces)) = local_store.get_item("traces") {
+        legacy = bundle::import(&saved_traces, Some(TraceFormat::Durable)).traces;
     }
+    // Saved traces may predate the current schema, so they go through the migrations too.
+    let current = match local_store.get_item("trace") {
+        Ok(Some(trace)) => parse_trace(&trace).ok(),
+        _ => None,
+    };
+    let current_id = current
+        .as_ref()
+        .map(|trace| trace.durable_execution_id.clone());
+    if let Some(trace) = current {
+        legacy.retain(|other| other.durable_execution_id != trace.durable_execution_id);
+        legacy.push(trace);
+    }
+    (current_id, legacy)
+}
+
+fn remove_legacy_traces(local_store: &web_sys::Storage) {
+    let _ = local_store.remove_item("traces");
+    let _ = local_store.remove_item("trace");
 }
 
 fn main() {
     sycamore::render(|| {
         let state = State(create_signal(None));
         provide_context(state);
+        provide_context(Traces(create_signal(Vec::new())));
+        provide_context(TraceStore::new());
+        provide_context(Navigation(create_signal(Page::Trace)));
+        provide_context(TimelineView::new());
+        provide_context(Settings::load());
         App()
     })
 }
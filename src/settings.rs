@@ -0,0 +1,322 @@
+//! How times are displayed and how the app looks, chosen in the settings panel and kept in
+//! localStorage.
+
+use durable_trace::analysis;
+use durable_trace::chrono::{DateTime, Utc};
+use durable_trace::export::TimeBase;
+use js_sys::{Array, Function, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use sycamore::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+const SETTINGS_KEY: &str = "settings";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum TimeZoneSetting {
+    /// The browser's zone.
+    #[default]
+    Local,
+    Utc,
+    /// An IANA zone such as `Europe/Lisbon`.
+    Named(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ClockFormat {
+    #[default]
+    TwentyFourHour,
+    TwelveHour,
+}
+
+/// How relative mode writes a time's offset from the start of the execution.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RelativeFormat {
+    /// `+1h10m12s from start`.
+    #[default]
+    Compact,
+    /// `T+01:10:12.120`, what the timeline's T+ toggle switches to.
+    Precise,
+}
+
+impl RelativeFormat {
+    pub(crate) const ALL: [RelativeFormat; 2] = [RelativeFormat::Compact, RelativeFormat::Precise];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            RelativeFormat::Compact => "+1h10m12s from start",
+            RelativeFormat::Precise => "T+01:10:12.120",
+        }
+    }
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            RelativeFormat::Compact => "compact",
+            RelativeFormat::Precise => "precise",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Self {
+        RelativeFormat::ALL
+            .into_iter()
+            .find(|format| format.key() == key)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Theme {
+    /// Dark and high contrast as the operating system asks for them.
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Theme {
+    pub(crate) const ALL: [Theme; 4] = [
+        Theme::System,
+        Theme::Light,
+        Theme::Dark,
+        Theme::HighContrast,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "Follow system",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::HighContrast => "High contrast",
+        }
+    }
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Self {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.key() == key)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SavedSettings {
+    zone: TimeZoneSetting,
+    clock: ClockFormat,
+    relative: bool,
+    relative_format: RelativeFormat,
+    theme: Theme,
+    colour_blind: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Settings {
+    pub(crate) zone: Signal<TimeZoneSetting>,
+    pub(crate) clock: Signal<ClockFormat>,
+    /// Show times as offsets from the start of the execution, in the app and in exports.
+    pub(crate) relative: Signal<bool>,
+    pub(crate) relative_format: Signal<RelativeFormat>,
+    pub(crate) theme: Signal<Theme>,
+    /// Status and step colours that do not rely on telling red from green.
+    pub(crate) colour_blind: Signal<bool>,
+    // An `Intl.DateTimeFormat` for the zone and clock, or why it could not be created.
+    formatter: ReadSignal<Result<JsValue, String>>,
+}
+
+impl Settings {
+    /// Reads the saved settings and keeps saving them as they change.
+    pub(crate) fn load() -> Self {
+        let local_store = window().local_storage().ok().flatten();
+        let saved: SavedSettings = local_store
+            .as_ref()
+            .and_then(|local_store| local_store.get_item(SETTINGS_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        let zone = create_signal(saved.zone);
+        let clock = create_signal(saved.clock);
+        let relative = create_signal(saved.relative);
+        let relative_format = create_signal(saved.relative_format);
+        let theme = create_signal(saved.theme);
+        let colour_blind = create_signal(saved.colour_blind);
+        apply_theme(theme, colour_blind);
+        let formatter = create_memo(move || zone.with(|zone| formatter(zone, clock.get())));
+        create_effect(move || {
+            let saved = SavedSettings {
+                zone: zone.get_clone(),
+                clock: clock.get(),
+                relative: relative.get(),
+                relative_format: relative_format.get(),
+                theme: theme.get(),
+                colour_blind: colour_blind.get(),
+            };
+            if let (Some(local_store), Ok(json)) = (&local_store, serde_json::to_string(&saved)) {
+                let _ = local_store.set_item(SETTINGS_KEY, &json);
+            }
+        });
+        Settings {
+            zone,
+            clock,
+            relative,
+            relative_format,
+            theme,
+            colour_blind,
+            formatter,
+        }
+    }
+
+    /// Why the chosen zone cannot be used, e.g. an unknown IANA name.
+    pub(crate) fn zone_error(&self) -> Option<String> {
+        self.formatter
+            .with(|formatter| formatter.as_ref().err().cloned())
+    }
+
+    /// `at` in the chosen zone and clock, or as an offset from `origin` in relative mode.
+    pub(crate) fn format_time(&self, at: DateTime<Utc>, origin: DateTime<Utc>) -> String {
+        if !self.relative.get() {
+            return self.format_absolute(at);
+        }
+        match self.relative_format.get() {
+            RelativeFormat::Compact => {
+                let offset = analysis::compact_duration(at - origin);
+                let sign = if offset.starts_with('-') { "" } else { "+" };
+                format!("{}{} from start", sign, offset)
+            }
+            RelativeFormat::Precise => analysis::relative_time(at, origin),
+        }
+    }
+
+    /// Whether times are shown as `T+HH:MM:SS.mmm`, the state of the T+ toggle.
+    pub(crate) fn precise_relative(&self) -> bool {
+        self.relative.get() && self.relative_format.get() == RelativeFormat::Precise
+    }
+
+    /// Switches between absolute times and `T+HH:MM:SS.mmm`, leaving the format chosen in the
+    /// settings panel alone when going back to absolute times.
+    pub(crate) fn toggle_precise_relative(&self) {
+        if untrack(|| self.precise_relative()) {
+            self.relative.set(false);
+        } else {
+            self.relative_format.set(RelativeFormat::Precise);
+            self.relative.set(true);
+        }
+    }
+
+    pub(crate) fn time_base(&self) -> TimeBase {
+        if self.relative.get() {
+            TimeBase::Relative
+        } else {
+            TimeBase::Absolute
+        }
+    }
+
+    /// `at` in the chosen zone and clock, ignoring relative mode.
+    pub(crate) fn format_absolute(&self, at: DateTime<Utc>) -> String {
+        self.formatter.with(|formatter| {
+            let date = js_sys::Date::new(&JsValue::from_f64(at.timestamp_millis() as f64));
+            formatter
+                .as_ref()
+                .ok()
+                .and_then(|formatter| Reflect::get(formatter, &JsValue::from_str("format")).ok())
+                .and_then(|format| format.dyn_into::<Function>().ok())
+                .and_then(|format| format.call1(&JsValue::NULL, &date).ok())
+                .and_then(|text| text.as_string())
+                .unwrap_or_else(|| at.to_rfc3339())
+        })
+    }
+}
+
+// Themes are classes on `<html>` that tailwind.config.js maps to colour variables. Following the
+// system tracks the media queries, so switching the OS theme restyles the app straight away.
+fn apply_theme(theme: Signal<Theme>, colour_blind: Signal<bool>) {
+    let system_dark = media_query_signal("(prefers-color-scheme: dark)");
+    let system_contrast = media_query_signal("(prefers-contrast: more)");
+    create_effect(move || {
+        let (dark, contrast) = match theme.get() {
+            Theme::System => (system_dark.get(), system_contrast.get()),
+            Theme::Light => (false, false),
+            Theme::Dark => (true, false),
+            Theme::HighContrast => (system_dark.get(), true),
+        };
+        let Some(root) = document().document_element() else {
+            return;
+        };
+        let classes = root.class_list();
+        for (class, enabled) in [
+            ("dark", dark),
+            ("contrast", contrast),
+            ("colour-blind", colour_blind.get()),
+        ] {
+            let _ = classes.toggle_with_force(class, enabled);
+        }
+    });
+}
+
+// Whether `query` matches, updated whenever that changes. Settings live as long as the app, so
+// the listener is never removed.
+fn media_query_signal(query: &str) -> ReadSignal<bool> {
+    let matches = create_signal(false);
+    if let Ok(Some(list)) = window().match_media(query) {
+        matches.set(list.matches());
+        let on_change = Closure::<dyn FnMut(web_sys::MediaQueryListEvent)>::new(
+            move |event: web_sys::MediaQueryListEvent| matches.set(event.matches()),
+        );
+        let _ = list.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref());
+        on_change.forget();
+    }
+    *matches
+}
+
+// `Intl.DateTimeFormat` throws a RangeError for unknown zones, so it is constructed through
+// `Reflect` to get the error back instead of aborting.
+fn formatter(zone: &TimeZoneSetting, clock: ClockFormat) -> Result<JsValue, String> {
+    let options = Object::new();
+    let mut entries = vec![
+        ("year", JsValue::from_str("numeric")),
+        ("month", JsValue::from_str("2-digit")),
+        ("day", JsValue::from_str("2-digit")),
+        ("hour", JsValue::from_str("2-digit")),
+        ("minute", JsValue::from_str("2-digit")),
+        ("second", JsValue::from_str("2-digit")),
+        ("fractionalSecondDigits", JsValue::from_f64(3.0)),
+        ("timeZoneName", JsValue::from_str("short")),
+        (
+            "hour12",
+            JsValue::from_bool(clock == ClockFormat::TwelveHour),
+        ),
+    ];
+    match zone {
+        TimeZoneSetting::Local => {}
+        TimeZoneSetting::Utc => entries.push(("timeZone", JsValue::from_str("UTC"))),
+        TimeZoneSetting::Named(name) => entries.push(("timeZone", JsValue::from_str(name))),
+    }
+    for (key, value) in entries {
+        Reflect::set(&options, &JsValue::from_str(key), &value).map_err(describe)?;
+    }
+    let constructor = Reflect::get(&js_sys::global(), &JsValue::from_str("Intl"))
+        .and_then(|intl| Reflect::get(&intl, &JsValue::from_str("DateTimeFormat")))
+        .and_then(|constructor| constructor.dyn_into::<Function>())
+        .map_err(describe)?;
+    Reflect::construct(&constructor, &Array::of2(&JsValue::UNDEFINED, &options)).map_err(describe)
+}
+
+fn describe(error: JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .unwrap_or_else(|| format!("{:?}", error))
+}
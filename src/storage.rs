@@ -0,0 +1,324 @@
+//! Persists loaded traces in IndexedDB, which unlike localStorage holds more than a few
+//! megabytes and reports running out of space as an error instead of throwing.
+
+use durable_trace::{parse_trace, DurableTrace};
+use js_sys::{Array, Function, Object, Promise, Reflect};
+use std::fmt;
+use sycamore::prelude::*;
+use sycamore::rt::console_error;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{DomException, IdbDatabase, IdbRequest, IdbTransaction, IdbTransactionMode};
+
+const DB_NAME: &str = "durable-trace";
+const DB_VERSION: u32 = 1;
+const STORE: &str = "traces";
+
+// Oldest traces are evicted beyond either limit.
+const MAX_STORED_TRACES: usize = 200;
+const MAX_STORED_BYTES: usize = 200 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StorageError {
+    Unavailable(String),
+    QuotaExceeded,
+    Failed(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Unavailable(reason) => write!(f, "Storage is unavailable: {}", reason),
+            StorageError::QuotaExceeded => write!(f, "The browser storage quota is exceeded"),
+            StorageError::Failed(reason) => write!(f, "Storage failed: {}", reason),
+        }
+    }
+}
+
+impl From<JsValue> for StorageError {
+    fn from(value: JsValue) -> Self {
+        match value.dyn_into::<DomException>() {
+            Ok(exception) if exception.name() == "QuotaExceededError" => {
+                StorageError::QuotaExceeded
+            }
+            Ok(exception) => StorageError::Failed(exception.message()),
+            Err(value) => StorageError::Failed(format!("{:?}", value)),
+        }
+    }
+}
+
+/// What is kept about a stored trace besides the trace itself.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StoredEntry {
+    pub(crate) durable_execution_id: String,
+    pub(crate) name: String,
+    /// Bytes of serialised JSON.
+    pub(crate) size: usize,
+    /// Milliseconds since the epoch.
+    pub(crate) saved_at: f64,
+}
+
+/// The traces saved in the browser, mirrored in signals for the UI.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TraceStore {
+    db: Signal<Option<IdbDatabase>>,
+    /// Oldest first.
+    pub(crate) entries: Signal<Vec<StoredEntry>>,
+    /// The last error, cleared by the next successful save.
+    pub(crate) error: Signal<Option<String>>,
+    /// Whether the saved traces have been read back yet.
+    pub(crate) ready: Signal<bool>,
+}
+
+impl TraceStore {
+    pub(crate) fn new() -> Self {
+        TraceStore {
+            db: create_signal(None),
+            entries: create_signal(Vec::new()),
+            error: create_signal(None),
+            ready: create_signal(false),
+        }
+    }
+
+    /// Opens the database and reads back every stored trace, oldest first.
+    pub(crate) async fn open(self) -> Result<Vec<DurableTrace>, StorageError> {
+        let db = open_db().await?;
+        let transaction = db.transaction_with_str(STORE)?;
+        let records: Array = wait(&transaction.object_store(STORE)?.get_all()?)
+            .await?
+            .unchecked_into();
+
+        let mut loaded = Vec::new();
+        for record in records.iter() {
+            let Some(json) = field(&record, "json").as_string() else {
+                continue;
+            };
+            // Stored traces may predate the current schema, so they go through the migrations.
+            match parse_trace(&json) {
+                Ok(trace) => loaded.push((entry_of(&record, &trace), trace)),
+                Err(e) => console_error!("Skipping a stored trace: {}", e),
+            }
+        }
+        loaded.sort_by(|(a, _), (b, _)| a.saved_at.total_cmp(&b.saved_at));
+        let (entries, traces): (Vec<_>, Vec<_>) = loaded.into_iter().unzip();
+        self.entries.set(entries);
+        self.db.set(Some(db));
+        Ok(traces)
+    }
+
+    /// Saves `traces` in the background, evicting the oldest entries to stay within the limits
+    /// or when the browser runs out of space.
+    pub(crate) fn save(self, traces: Vec<DurableTrace>) {
+        spawn_local(async move {
+            let _ = self.persist(&traces).await;
+        });
+    }
+
+    /// Like [`TraceStore::save`], for callers that need to know whether the traces made it.
+    pub(crate) async fn persist(self, traces: &[DurableTrace]) -> Result<(), StorageError> {
+        let Some(db) = self.db.get_clone_untracked() else {
+            return Err(StorageError::Unavailable(
+                "the database is not open".to_string(),
+            ));
+        };
+        let result = self.save_records(&db, traces).await;
+        match &result {
+            Ok(()) => self.error.set(None),
+            Err(e) => {
+                console_error!("{}", e);
+                self.error.set(Some(format!(
+                    "Could not save {} trace(s): {}",
+                    traces.len(),
+                    e
+                )));
+            }
+        }
+        result
+    }
+
+    /// Deletes the trace with `durable_execution_id` from storage.
+    pub(crate) fn remove(self, durable_execution_id: String) {
+        let Some(db) = self.db.get_clone_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            if let Err(e) = self.delete(&db, &durable_execution_id).await {
+                console_error!("{}", e);
+                self.error.set(Some(e.to_string()));
+            }
+        });
+    }
+
+    async fn save_records(
+        self,
+        db: &IdbDatabase,
+        traces: &[DurableTrace],
+    ) -> Result<(), StorageError> {
+        let saved_at = js_sys::Date::now();
+        let records = traces
+            .iter()
+            .map(|trace| {
+                let json = serde_json::to_string(trace)
+                    .map_err(|e| StorageError::Failed(e.to_string()))?;
+                let entry = StoredEntry {
+                    durable_execution_id: trace.durable_execution_id.clone(),
+                    name: trace.name.clone(),
+                    size: json.len(),
+                    saved_at,
+                };
+                Ok((entry, json))
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+        let not_in_batch = |entry: &StoredEntry| {
+            !records
+                .iter()
+                .any(|(record, _)| record.durable_execution_id == entry.durable_execution_id)
+        };
+
+        // Out of space: make room by dropping the oldest other trace, then try again.
+        while let Err(e) = put(db, &records).await {
+            let oldest = self.entries.with_untracked(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| not_in_batch(entry))
+                    .map(|entry| entry.durable_execution_id.clone())
+            });
+            match (e, oldest) {
+                (StorageError::QuotaExceeded, Some(oldest)) => self.delete(db, &oldest).await?,
+                (e, _) => return Err(e),
+            }
+        }
+        self.entries.update(|entries| {
+            entries.retain(|entry| not_in_batch(entry));
+            entries.extend(records.iter().map(|(entry, _)| entry.clone()));
+        });
+
+        loop {
+            let oldest = self.entries.with_untracked(|entries| {
+                let total: usize = entries.iter().map(|entry| entry.size).sum();
+                let over_limit = entries.len() > MAX_STORED_TRACES || total > MAX_STORED_BYTES;
+                entries
+                    .iter()
+                    .find(|entry| over_limit && not_in_batch(entry))
+                    .map(|entry| entry.durable_execution_id.clone())
+            });
+            match oldest {
+                Some(oldest) => self.delete(db, &oldest).await?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    async fn delete(
+        self,
+        db: &IdbDatabase,
+        durable_execution_id: &str,
+    ) -> Result<(), StorageError> {
+        let transaction = db.transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?;
+        transaction
+            .object_store(STORE)?
+            .delete(&JsValue::from_str(durable_execution_id))?;
+        completion(&transaction).await?;
+        self.entries.update(|entries| {
+            entries.retain(|entry| entry.durable_execution_id != durable_execution_id)
+        });
+        Ok(())
+    }
+}
+
+async fn open_db() -> Result<IdbDatabase, StorageError> {
+    let factory = window()
+        .indexed_db()
+        .ok()
+        .flatten()
+        .ok_or_else(|| StorageError::Unavailable("IndexedDB is not supported".to_string()))?;
+    let request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+        let Ok(db) = upgrade_request.result() else {
+            return;
+        };
+        let db: IdbDatabase = db.unchecked_into();
+        if !db.object_store_names().contains(STORE) {
+            if let Err(e) = db.create_object_store(STORE) {
+                console_error!("Could not create the trace store: {:?}", e);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+    Ok(wait(&request).await?.unchecked_into())
+}
+
+// Traces are keyed by execution id, so saving an execution again replaces it.
+async fn put(db: &IdbDatabase, records: &[(StoredEntry, String)]) -> Result<(), StorageError> {
+    let transaction = db.transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?;
+    let store = transaction.object_store(STORE)?;
+    for (entry, json) in records {
+        let record = Object::new();
+        for (key, value) in [
+            ("name", JsValue::from_str(&entry.name)),
+            ("size", JsValue::from_f64(entry.size as f64)),
+            ("savedAt", JsValue::from_f64(entry.saved_at)),
+            ("json", JsValue::from_str(json)),
+        ] {
+            Reflect::set(&record, &JsValue::from_str(key), &value)?;
+        }
+        store.put_with_key(&record, &JsValue::from_str(&entry.durable_execution_id))?;
+    }
+    completion(&transaction).await
+}
+
+fn entry_of(record: &JsValue, trace: &DurableTrace) -> StoredEntry {
+    StoredEntry {
+        durable_execution_id: trace.durable_execution_id.clone(),
+        name: trace.name.clone(),
+        size: field(record, "size").as_f64().unwrap_or_default() as usize,
+        saved_at: field(record, "savedAt").as_f64().unwrap_or_default(),
+    }
+}
+
+fn field(record: &JsValue, key: &str) -> JsValue {
+    Reflect::get(record, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED)
+}
+
+// Resolves with the request's result once it succeeds.
+async fn wait(request: &IdbRequest) -> Result<JsValue, StorageError> {
+    let promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let error_request = request.clone();
+        let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+            let error = error_request.error().ok().flatten().map(JsValue::from);
+            let _ = reject.call1(&JsValue::NULL, &error.unwrap_or(JsValue::UNDEFINED));
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    Ok(JsFuture::from(promise).await?)
+}
+
+// Quota errors surface when the transaction aborts rather than on the request that caused them.
+async fn completion(transaction: &IdbTransaction) -> Result<(), StorageError> {
+    let promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let on_complete = Closure::once_into_js(move |_: web_sys::Event| {
+            let _ = resolve.call0(&JsValue::NULL);
+        });
+        let failed_transaction = transaction.clone();
+        let on_failure = Closure::once_into_js(move |_: web_sys::Event| {
+            // A failed transaction fires `error` and then `abort`, and this closure runs once.
+            failed_transaction.set_onerror(None);
+            failed_transaction.set_onabort(None);
+            let error = failed_transaction.error().map(JsValue::from);
+            let _ = reject.call1(&JsValue::NULL, &error.unwrap_or(JsValue::UNDEFINED));
+        });
+        transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+        transaction.set_onerror(Some(on_failure.unchecked_ref()));
+        transaction.set_onabort(Some(on_failure.unchecked_ref()));
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use durable_trace::{analysis, StepTrace};
+use sycamore::prelude::*;
+
+const MIN_ZOOM: f64 = 1.0;
+const MAX_ZOOM: f64 = 16.0;
+const ZOOM_STEP: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StepSort {
+    TraceOrder,
+    ScheduledAt,
+    LongestFirst,
+    StepId,
+}
+
+impl StepSort {
+    pub(crate) const ALL: [StepSort; 4] = [
+        StepSort::TraceOrder,
+        StepSort::ScheduledAt,
+        StepSort::LongestFirst,
+        StepSort::StepId,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            StepSort::TraceOrder => "Trace order",
+            StepSort::ScheduledAt => "Scheduled at",
+            StepSort::LongestFirst => "Longest first",
+            StepSort::StepId => "Step id",
+        }
+    }
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            StepSort::TraceOrder => "trace",
+            StepSort::ScheduledAt => "scheduled",
+            StepSort::LongestFirst => "duration",
+            StepSort::StepId => "id",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Self {
+        StepSort::ALL
+            .into_iter()
+            .find(|sort| sort.key() == key)
+            .unwrap_or(StepSort::TraceOrder)
+    }
+}
+
+/// Bars on a time axis, or the same steps as a table for screen readers and copying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StepLayout {
+    Timeline,
+    Table,
+}
+
+impl StepLayout {
+    pub(crate) const ALL: [StepLayout; 2] = [StepLayout::Timeline, StepLayout::Table];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            StepLayout::Timeline => "Timeline",
+            StepLayout::Table => "Table",
+        }
+    }
+}
+
+// How the steps timeline is currently shown. Exports read it too so they match the screen.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TimelineView {
+    pub(crate) filter: Signal<String>,
+    pub(crate) sort: Signal<StepSort>,
+    pub(crate) zoom: Signal<f64>,
+    pub(crate) layout: Signal<StepLayout>,
+    /// Trace index of the step picked with the keyboard or by clicking its bar.
+    pub(crate) selected: Signal<Option<usize>>,
+}
+
+impl TimelineView {
+    pub(crate) fn new() -> Self {
+        TimelineView {
+            filter: create_signal(String::new()),
+            sort: create_signal(StepSort::TraceOrder),
+            zoom: create_signal(MIN_ZOOM),
+            layout: create_signal(StepLayout::Timeline),
+            selected: create_signal(None),
+        }
+    }
+
+    pub(crate) fn zoom_in(&self) {
+        self.zoom
+            .update(|zoom| *zoom = (*zoom * ZOOM_STEP).min(MAX_ZOOM));
+    }
+
+    pub(crate) fn zoom_out(&self) {
+        self.zoom
+            .update(|zoom| *zoom = (*zoom / ZOOM_STEP).max(MIN_ZOOM));
+    }
+
+    /// Indexes into `steps` of the steps matching the current filter, in the current order.
+    ///
+    /// Indexes are what rows are keyed by, step ids are not guaranteed to be unique. Nothing is
+    /// cloned, so traces with thousands of steps stay cheap to filter.
+    pub(crate) fn visible_steps(&self, steps: &[StepTrace]) -> Vec<usize> {
+        let filter = self.filter.with(|filter| filter.trim().to_lowercase());
+        let mut visible = steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| matches_filter(step, &filter))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        match self.sort.get() {
+            StepSort::TraceOrder => {}
+            StepSort::ScheduledAt => visible.sort_by_key(|index| steps[*index].scheduled_at),
+            // Steps still running go last, they have no duration yet.
+            StepSort::LongestFirst => visible.sort_by_key(|index| {
+                std::cmp::Reverse(
+                    analysis::step_duration(&steps[*index]).map(|d| d.num_milliseconds()),
+                )
+            }),
+            StepSort::StepId => {
+                visible.sort_by(|a, b| steps[*a].durable_step_id.cmp(&steps[*b].durable_step_id))
+            }
+        }
+        visible
+    }
+}
+
+// Case-insensitive match on the step id and the task names involved. `filter` is already
+// trimmed and lowercased.
+fn matches_filter(step: &StepTrace, filter: &str) -> bool {
+    filter.is_empty()
+        || contains_lowercase(&step.durable_step_id, filter)
+        || contains_lowercase(&step.in_task_info.task_name, filter)
+        || step
+            .out_task_info
+            .as_ref()
+            .is_some_and(|info| contains_lowercase(&info.task_name, filter))
+}
+
+// Ids and task names are nearly always ASCII, which compares without allocating a lowercase copy.
+fn contains_lowercase(text: &str, filter: &str) -> bool {
+    if text.is_ascii() {
+        text.as_bytes()
+            .windows(filter.len())
+            .any(|window| window.eq_ignore_ascii_case(filter.as_bytes()))
+    } else {
+        text.to_lowercase().contains(filter)
+    }
+}
@@ -0,0 +1,176 @@
+//! Windowed rendering for long lists of fixed-height rows. Only the rows near the visible part of
+//! the page are mounted, so a trace with thousands of steps costs as much DOM as one with fifty.
+
+use crate::browser;
+use std::collections::HashMap;
+use std::ops::Range;
+use sycamore::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+// Rows mounted above and below the visible ones, so fast scrolling does not show blank space.
+const OVERSCAN: usize = 8;
+
+/// Where the window is over a list whose rows are all `row_height` pixels tall.
+#[derive(Clone, Copy)]
+pub(crate) struct Viewport {
+    list: NodeRef,
+    row_height: f64,
+    // Top of the window relative to the top of the list, so negative while the list is below it.
+    top: Signal<f64>,
+    height: Signal<f64>,
+}
+
+impl Viewport {
+    /// Follows window scrolls and resizes until the current reactive scope is disposed. Attach
+    /// `list_ref` to the element holding the rows.
+    pub(crate) fn new(row_height: f64) -> Self {
+        let viewport = Self {
+            list: create_node_ref(),
+            row_height,
+            top: create_signal(0.0),
+            height: create_signal(browser::viewport_height()),
+        };
+        on_mount(move || viewport.measure());
+        browser::on_window_event("scroll", move |_| viewport.measure());
+        browser::on_window_event("resize", move |_| viewport.measure());
+        viewport
+    }
+
+    pub(crate) fn list_ref(&self) -> NodeRef {
+        self.list
+    }
+
+    // The node ref and signals share the scope `new` was called in, so are disposed together.
+    fn is_alive(&self) -> bool {
+        self.top.is_alive() && self.height.is_alive()
+    }
+
+    fn measure(&self) {
+        if let Some(top) = self
+            .list
+            .try_get()
+            .and_then(|list| browser::element_top(&list))
+        {
+            self.top.set(-top);
+        }
+        self.height.set(browser::viewport_height());
+    }
+
+    /// Positions of the rows worth mounting in a list of `len` rows.
+    pub(crate) fn rows(&self, len: usize) -> Range<usize> {
+        let (top, height) = (self.top.get(), self.height.get());
+        let first = (top / self.row_height).floor().max(0.0) as usize;
+        let last = ((top + height) / self.row_height).ceil().max(0.0) as usize;
+        let start = first.saturating_sub(OVERSCAN).min(len);
+        start..(last + OVERSCAN).clamp(start, len)
+    }
+
+    /// The rows worth mounting, as `(position, index, item)`, where `visible` lists indexes into
+    /// `items` in display order. Only the mounted items are cloned.
+    pub(crate) fn mounted<T: Clone + 'static>(
+        &self,
+        items: ReadSignal<Vec<T>>,
+        visible: ReadSignal<Vec<usize>>,
+    ) -> ReadSignal<Vec<(usize, usize, T)>> {
+        let viewport = *self;
+        let rows = create_selector(move || viewport.rows(visible.with(Vec::len)));
+        create_memo(move || {
+            let rows = rows.get_clone();
+            visible.with(|visible| {
+                items.with(|items| {
+                    visible[rows.clone()]
+                        .iter()
+                        .zip(rows)
+                        .map(|(index, position)| (position, *index, items[*index].clone()))
+                        .collect()
+                })
+            })
+        })
+    }
+
+    /// The part of a list of `len` rows that is on screen, as fractions from its top.
+    pub(crate) fn visible_fraction(&self, len: usize) -> (f64, f64) {
+        let total = len as f64 * self.row_height;
+        if total <= 0.0 {
+            return (0.0, 1.0);
+        }
+        let top = self.top.get().clamp(0.0, total);
+        let bottom = (self.top.get() + self.height.get()).clamp(top, total);
+        (top / total, bottom / total)
+    }
+
+    /// Scrolls the page as little as possible to show the row at `position` whole.
+    pub(crate) fn reveal(&self, position: usize) {
+        self.measure();
+        let top = position as f64 * self.row_height - self.top.get_untracked();
+        let bottom = top + self.row_height;
+        if top < 0.0 {
+            browser::scroll_window_by(top);
+        } else if bottom > self.height.get_untracked() {
+            browser::scroll_window_by(bottom - self.height.get_untracked());
+        }
+    }
+
+    /// Scrolls the page so the point `rows` rows into the list is in the middle of the screen.
+    pub(crate) fn centre_on(&self, rows: f64) {
+        self.measure();
+        let target = rows * self.row_height - self.height.get_untracked() / 2.0;
+        browser::scroll_window_by(target - self.top.get_untracked());
+    }
+
+    /// Keeps the list steady while `keys` changes under it, e.g. while filtering: the first row
+    /// on screen that survives the change stays at the top of the window. Also brings the
+    /// `selected` key on screen, since its row may not be mounted.
+    pub(crate) fn follow(&self, keys: ReadSignal<Vec<usize>>, selected: Signal<Option<usize>>) {
+        let viewport = *self;
+        let previous = create_signal(Vec::new());
+        create_effect(move || {
+            let current = keys.get_clone();
+            let previous = previous.replace_silent(current.clone());
+            let top = viewport.top.get_untracked();
+            // Nothing to keep in place while the start of the list is on screen.
+            if previous.is_empty() || top <= 0.0 {
+                return;
+            }
+            let first = (top / viewport.row_height).floor() as usize;
+            let positions = current
+                .iter()
+                .enumerate()
+                .map(|(position, key)| (*key, position))
+                .collect::<HashMap<_, _>>();
+            let Some((old_position, new_position)) = previous
+                .iter()
+                .enumerate()
+                .skip(first)
+                .find_map(|(position, key)| positions.get(key).map(|new| (position, *new)))
+            else {
+                return;
+            };
+            // A row that was partly scrolled past keeps its offset; a later one lines up with the top.
+            let offset = if old_position == first {
+                top - first as f64 * viewport.row_height
+            } else {
+                0.0
+            };
+            let target = new_position as f64 * viewport.row_height + offset;
+            // The list's new height has to be laid out before the page can scroll into it.
+            spawn_local(async move {
+                // The list may have unmounted in the meantime, e.g. on a layout switch.
+                if !viewport.is_alive() {
+                    return;
+                }
+                viewport.measure();
+                browser::scroll_window_by(target - viewport.top.get_untracked());
+            });
+        });
+        create_effect(move || {
+            if let Some(selected) = selected.get() {
+                if let Some(position) =
+                    keys.with_untracked(|keys| keys.iter().position(|key| *key == selected))
+                {
+                    viewport.reveal(position);
+                }
+            }
+        });
+    }
+}
@@ -1,4 +1,75 @@
+const colors = require("tailwindcss/colors");
+const plugin = require("tailwindcss/plugin");
+
+// Colour scales the app uses. Each shade is a CSS variable so themes can swap them without
+// touching the classes in the components.
+const SCALES = ["gray", "blue", "indigo", "amber", "red", "green", "teal", "purple"];
+const SHADES = [50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 950];
+
+// Dark mode reads every scale backwards: light surfaces become dark and dark text light.
+const DARK = Object.fromEntries(SHADES.map((shade, i) => [shade, SHADES[SHADES.length - 1 - i]]));
+// High contrast keeps surfaces and pushes muted text, borders and accents to the extremes.
+const CONTRAST = {
+  50: 50, 100: 100, 200: 500, 300: 700, 400: 800, 500: 900, 600: 900,
+  700: 950, 800: 950, 900: 950, 950: 950,
+};
+const DARK_CONTRAST = {
+  50: 950, 100: 950, 200: 600, 300: 300, 400: 200, 500: 100, 600: 100,
+  700: 50, 800: 50, 900: 50, 950: 50,
+};
+// Success and failure move to a blue-orange axis, which stays distinct with red-green colour
+// blindness; outlier highlights move to yellow so they do not blend into failures.
+const COLOUR_BLIND = { green: "sky", red: "orange", amber: "yellow" };
+
+const rgb = (hex) =>
+  [1, 3, 5].map((i) => parseInt(hex.slice(i, i + 2), 16)).join(" ");
+
+// `white` is the card and page surface, so it follows the theme as well.
+function variables(mapping, white, substitutes = {}) {
+  const vars = {};
+  for (const scale of SCALES) {
+    const source = colors[substitutes[scale] || scale];
+    for (const shade of SHADES) {
+      vars[`--${scale}-${shade}`] = rgb(source[mapping[shade]]);
+    }
+  }
+  vars["--white"] = rgb(white);
+  return vars;
+}
+
+const identity = Object.fromEntries(SHADES.map((shade) => [shade, shade]));
+const themed = (name) =>
+  Object.fromEntries(SHADES.map((shade) => [shade, `rgb(var(--${name}-${shade}) / <alpha-value>)`]));
+
 module.exports = {
   content: ["./src/**/*.rs", "./index.html"],
-  plugins: [require("@tailwindcss/typography")],
+  darkMode: "class",
+  theme: {
+    extend: {
+      colors: {
+        white: "rgb(var(--white) / <alpha-value>)",
+        ...Object.fromEntries(SCALES.map((scale) => [scale, themed(scale)])),
+      },
+    },
+  },
+  plugins: [
+    require("@tailwindcss/typography"),
+    // Classes on <html> set by the app: `dark`, `contrast` and `colour-blind`.
+    plugin(({ addBase }) => {
+      const themes = [
+        [":root", identity, "#ffffff"],
+        [":root.dark", DARK, colors.gray[900]],
+        [":root.contrast", CONTRAST, "#ffffff"],
+        [":root.dark.contrast", DARK_CONTRAST, "#000000"],
+      ];
+      const base = {};
+      for (const [selector, mapping, white] of themes) {
+        base[selector] = variables(mapping, white);
+        base[`${selector}.colour-blind`] = variables(mapping, white, COLOUR_BLIND);
+      }
+      base[":root.dark"].colorScheme = "dark";
+      base["body"] = { backgroundColor: "rgb(var(--white))", color: "rgb(var(--gray-900))" };
+      addBase(base);
+    }),
+  ],
 };